@@ -1,91 +1,269 @@
-use std::fmt;
-use std::error::Error;
+use std::time::Duration;
 
+use async_trait::async_trait;
 use dotenv::dotenv;
-use reqwest::{header::*, Client};
-use chrono::{DateTime, FixedOffset};
-use crate::data::{Quotes, TimeSeries};
-use crate::api::endpoint::{ApiEndpoint, Requestable};
-
-pub type Result<T> = std::result::Result<T, ApiError>;
-
-#[derive(Debug, Clone)]
-pub enum ApiError { 
-    DeserializationError,
-    NetworkError { code: u16, msg: String },
-    ParseError,
-    SetUpError,
-    UnknownError
+use reqwest::{header::*, Client, Method, RequestBuilder, Url};
+use serde::de::DeserializeOwned;
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use crate::data::{
+    AccountHistory, Balances, Calendar, Clock, Expirations, History, OptionChain, OrderConfirmation, OrderPreview,
+    Orders, Positions, Profile, Quotes, Securities, StreamSession, Strikes, TimeSeries, Watchlist, Watchlists
+};
+use crate::api::config::ApiConfig;
+use crate::api::endpoint::{ApiEndpoint, HistoryInterval, Interval, Requestable, SessionFilter};
+use crate::api::order::OrderRequest;
+use crate::api::provider::{AccountProvider, MarketDataProvider};
+use crate::api::ratelimit::{RateBudget, RateLimiter};
+use crate::api::retry::{BreakerState, CircuitBreaker};
+use crate::api::stream::{self, EventStream};
+
+pub use crate::api::error::{ApiError, Result};
+
+/// TradierClient fetches market data from the Tradier brokerage API.
+///
+/// It is created once at startup and shared by every request, so connections
+/// to Tradier are pooled and kept alive between calls.
+pub struct TradierClient {
+    config: ApiConfig,
+    client: Client,
+    /// Like `client`, but without the overall timeout, which would cut a
+    /// stream off.
+    stream_client: Client,
+    limiter: RateLimiter,
+    breaker: CircuitBreaker
 }
 
+impl TradierClient {
+    pub fn new(config: ApiConfig) -> Result<Self> {
+        let api_key = match &config.api_key {
+            Some(key) => key.clone(),
+            None => get_tradier_api_key()?
+        };
+        let client = build_client(&api_key, &config, Some(config.timeout))?;
+        let stream_client = build_client(&api_key, &config, None)?;
+        let breaker = CircuitBreaker::new(config.breaker_threshold, config.breaker_cooldown);
+        Ok(TradierClient { config, client, stream_client, limiter: RateLimiter::new(), breaker })
+    }
+
+    /// Send a request, retrying transient failures with backoff. Only
+    /// transient failures count against the circuit breaker; a bad symbol
+    /// still means Tradier is up.
+    async fn get<T: DeserializeOwned>(&self, endpoint: ApiEndpoint) -> Result<T> {
+        let url = endpoint.url(&self.config.base_url())?;
+        let mut attempt = 0;
+        loop {
+            self.breaker.check()?;
+            match self.send(self.client.get(url.clone()), &url).await {
+                Err(err) if err.is_transient() => {
+                    self.breaker.record_failure();
+                    if attempt >= self.config.retry.max_retries {
+                        return Err(err);
+                    }
+                    tokio::time::delay_for(self.config.retry.delay(attempt)).await;
+                    attempt += 1;
+                },
+                result => {
+                    self.breaker.record_success();
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Post a form once. Orders aren't idempotent, so unlike `get` a failed
+    /// post is never retried: it may have gone through.
+    async fn post<T: DeserializeOwned>(&self, endpoint: ApiEndpoint, form: &[(String, String)]) -> Result<T> {
+        self.write(Method::POST, endpoint, form).await
+    }
+
+    async fn put<T: DeserializeOwned>(&self, endpoint: ApiEndpoint, form: &[(String, String)]) -> Result<T> {
+        self.write(Method::PUT, endpoint, form).await
+    }
 
-impl Error for ApiError { }
+    async fn write<T: DeserializeOwned>(&self, method: Method, endpoint: ApiEndpoint, form: &[(String, String)]) -> Result<T> {
+        let url = endpoint.url(&self.config.base_url())?;
+        self.breaker.check()?;
+        let result = self.send(self.client.request(method, url.clone()).form(form), &url).await;
+        match &result {
+            Err(err) if err.is_transient() => self.breaker.record_failure(),
+            _ => self.breaker.record_success()
+        }
+        result
+    }
 
-impl fmt::Display for ApiError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", *self)
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder, url: &Url) -> Result<T> {
+        self.limiter.acquire().await;
+        let res = request.send().await
+            .map_err(|err| ApiError::from_reqwest(url, err))?;
+        self.limiter.update(res.headers());
+        let status = res.status();
+        let body = res.text().await
+            .map_err(|err| ApiError::from_reqwest(url, err))?;
+        if !status.is_success() {
+            return Err(ApiError::from_response(status.as_u16(), url, &body));
+        }
+        serde_json::from_str(&body).map_err(|err| ApiError::from_serde(url, err))
     }
 }
 
-pub async fn get_stock_quotes(symbols: Vec<String>) -> Result<Quotes> {
-    let client = get_client()?;
-    let url = ApiEndpoint::Quotes { symbols }.url()?;
+#[async_trait]
+impl MarketDataProvider for TradierClient {
+    fn name(&self) -> &str {
+        "Tradier"
+    }
+
+    fn rate_budget(&self) -> Option<RateBudget> {
+        self.limiter.budget()
+    }
+
+    fn breaker_state(&self) -> Option<BreakerState> {
+        Some(self.breaker.state())
+    }
+
+    async fn get_stock_quotes(&self, symbols: Vec<String>) -> Result<Quotes> {
+        self.get(ApiEndpoint::Quotes { symbols }).await
+    }
+
+    async fn get_time_series_data(&self, symbol: String, start_date: DateTime<Tz>, end_date: DateTime<Tz>, interval: Interval, session_filter: SessionFilter) -> Result<TimeSeries> {
+        self.get(ApiEndpoint::TimeSeries {symbol, start_date, end_date, interval, session_filter}).await
+    }
+
+    async fn get_history(&self, symbol: String, interval: HistoryInterval, start: NaiveDate, end: NaiveDate) -> Result<History> {
+        self.get(ApiEndpoint::History { symbol, interval, start, end }).await
+    }
+
+    async fn get_calendar(&self, year: i32, month: u32) -> Result<Calendar> {
+        self.get(ApiEndpoint::Calendar { year, month }).await
+    }
+
+    async fn get_clock(&self) -> Result<Clock> {
+        self.get(ApiEndpoint::Clock).await
+    }
+
+    async fn search(&self, query: String) -> Result<Securities> {
+        self.get(ApiEndpoint::Search { query }).await
+    }
+
+    async fn lookup(&self, query: String) -> Result<Securities> {
+        self.get(ApiEndpoint::Lookup { query }).await
+    }
+
+    async fn get_option_expirations(&self, symbol: String) -> Result<Expirations> {
+        self.get(ApiEndpoint::OptionExpirations { symbol }).await
+    }
 
-    match client.get(url).send().await {
-        Ok(res) => {
-            let body = res.bytes().await
-                .map_err(|_| ApiError::DeserializationError)?;
-            let v = body.to_vec();
-            let s = String::from_utf8(v).map_err(|_| ApiError::UnknownError)?;
-            serde_json::from_str(&s).map_err(|_| ApiError::DeserializationError)
-        },
-        Err(err) => Err(parse_network_error(err))
+    async fn get_option_strikes(&self, symbol: String, expiration: NaiveDate) -> Result<Strikes> {
+        self.get(ApiEndpoint::OptionStrikes { symbol, expiration }).await
+    }
+
+    async fn get_option_chain(&self, symbol: String, expiration: NaiveDate) -> Result<OptionChain> {
+        self.get(ApiEndpoint::OptionChain { symbol, expiration }).await
+    }
+
+    /// Open a streaming session, then hold a connection to the URL it names.
+    /// Tradier doesn't stream from the sandbox.
+    async fn stream_events(&self, symbols: Vec<String>) -> Result<EventStream> {
+        let session: StreamSession = self.post(ApiEndpoint::StreamSession, &[]).await?;
+        let session = session.session();
+        let url = Url::parse(&session.url).map_err(|_| ApiError::InvalidUrl)?;
+        let form = [
+            ("sessionid", session.session_id.clone()),
+            ("symbols", symbols.join(",")),
+            ("filter", "quote,trade,summary".to_string()),
+            ("linebreak", "true".to_string())
+        ];
+        let res = self.stream_client.post(url.clone()).form(&form).send().await
+            .map_err(|err| ApiError::from_reqwest(&url, err))?;
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            return Err(ApiError::from_response(status.as_u16(), &url, &body));
+        }
+        Ok(stream::events(res, url))
     }
 }
 
-pub async fn get_time_series_data(symbol: String, start_date: DateTime<FixedOffset>, end_date: DateTime<FixedOffset>, interval: u8) -> Result<TimeSeries> {
-    let client = get_client()?;
-    let endpoint = ApiEndpoint::TimeSeries {symbol, start_date, end_date, interval};
-    let url = endpoint.url()?;
-
-    match client.get(url).send().await {
-        Ok(res) => {
-            if res.status() == 400 {
-                Err(ApiError::NetworkError{ code: 400, msg: "".to_string()})
-            } else {
-                let body = res.bytes().await
-                    .map_err(|_| ApiError::DeserializationError)?;
-                let v = body.to_vec();
-                let s = String::from_utf8(v).map_err(|_| ApiError::UnknownError)?;
-                serde_json::from_str(&s).map_err(|_| ApiError::DeserializationError)
-            }
-        },
-        Err(err) => Err(parse_network_error(err))
+#[async_trait]
+impl AccountProvider for TradierClient {
+    async fn get_profile(&self) -> Result<Profile> {
+        self.get(ApiEndpoint::Profile).await
+    }
+
+    async fn get_balances(&self, account_id: String) -> Result<Balances> {
+        self.get(ApiEndpoint::Balances { account_id }).await
+    }
+
+    async fn get_positions(&self, account_id: String) -> Result<Positions> {
+        self.get(ApiEndpoint::Positions { account_id }).await
+    }
+
+    async fn get_orders(&self, account_id: String) -> Result<Orders> {
+        self.get(ApiEndpoint::Orders { account_id }).await
+    }
+
+    async fn get_account_history(&self, account_id: String) -> Result<AccountHistory> {
+        self.get(ApiEndpoint::AccountHistory { account_id }).await
+    }
+
+    fn is_live(&self) -> bool {
+        self.config.is_live()
+    }
+
+    async fn preview_order(&self, account_id: String, order: OrderRequest) -> Result<OrderPreview> {
+        self.post(ApiEndpoint::PlaceOrder { account_id }, &order.form(true)).await
+    }
+
+    async fn place_order(&self, account_id: String, order: OrderRequest) -> Result<OrderConfirmation> {
+        if self.config.is_live() && !self.config.allow_live_orders {
+            return Err(ApiError::SetUp {
+                msg: "Live orders are disabled; pass --allow-live-orders to place them".to_string()
+            });
+        }
+        self.post(ApiEndpoint::PlaceOrder { account_id }, &order.form(false)).await
+    }
+
+    async fn get_watchlists(&self) -> Result<Watchlists> {
+        self.get(ApiEndpoint::Watchlists).await
+    }
+
+    async fn get_watchlist(&self, id: String) -> Result<Watchlist> {
+        self.get(ApiEndpoint::Watchlist { id }).await
+    }
+
+    async fn create_watchlist(&self, name: String, symbols: Vec<String>) -> Result<Watchlist> {
+        self.post(ApiEndpoint::Watchlists, &watchlist_form(name, symbols)).await
+    }
+
+    async fn update_watchlist(&self, id: String, name: String, symbols: Vec<String>) -> Result<Watchlist> {
+        self.put(ApiEndpoint::Watchlist { id }, &watchlist_form(name, symbols)).await
     }
 }
 
+fn watchlist_form(name: String, symbols: Vec<String>) -> Vec<(String, String)> {
+    vec![("name".to_string(), name), ("symbols".to_string(), symbols.join(","))]
+}
+
 fn get_tradier_api_key() -> Result<String> { 
     dotenv().ok();
     dotenv::var("TRADIER_API_KEY")
-        .map_err(|_| ApiError::SetUpError)
+        .map_err(|_| ApiError::SetUp { msg: "TRADIER_API_KEY is not set".to_string() })
 }
 
-fn get_client() -> Result<Client> {
+fn build_client(api_key: &str, config: &ApiConfig, timeout: Option<Duration>) -> Result<Client> {
     let mut headers = HeaderMap::new();
-    let auth = format!("Bearer {key}", key = get_tradier_api_key()?);
-    headers.append(AUTHORIZATION, auth.parse().unwrap());
+    let auth = format!("Bearer {key}", key = api_key);
+    let auth = auth.parse()
+        .map_err(|_| ApiError::SetUp { msg: "TRADIER_API_KEY is not a valid header value".to_string() })?;
+    headers.append(AUTHORIZATION, auth);
     headers.append(ACCEPT, "application/json".parse().unwrap());
-    Client::builder()
+    let builder = Client::builder()
         .default_headers(headers)
-        .build()
-        .map_err(|_| ApiError::SetUpError)
+        .connect_timeout(config.connect_timeout);
+    let builder = match timeout {
+        Some(timeout) => builder.timeout(timeout),
+        None => builder
+    };
+    builder.build()
+        .map_err(|err| ApiError::SetUp { msg: err.to_string() })
 }
-
-fn parse_network_error(err: reqwest::Error) -> ApiError { 
-    if let Some(status) = err.status() {
-        let code = status.as_u16();
-        let msg = status.as_str().parse().unwrap();
-        return ApiError::NetworkError { code, msg }
-    }
-    return ApiError::UnknownError
-}
\ No newline at end of file
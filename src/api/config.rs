@@ -0,0 +1,183 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use reqwest::Url;
+
+use crate::api::client::ApiError;
+use crate::api::retry::RetryPolicy;
+
+const SANDBOX_URL: &str = "https://sandbox.tradier.com/v1";
+const PRODUCTION_URL: &str = "https://api.tradier.com/v1";
+const PRODUCTION_HOST: &str = "api.tradier.com";
+
+/// Environment is the Tradier deployment the app talks to. Anything that
+/// isn't `sandbox` or `production` is treated as the base URL of a stand-in,
+/// e.g. a recording proxy or the `mock_tradier` binary.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Environment {
+    #[default]
+    Sandbox,
+    Production,
+    Custom(Url)
+}
+
+impl Environment {
+    /// This environment, unless `base_url` is given to override it.
+    pub fn with_base_url(self, base_url: Option<Url>) -> Self {
+        base_url.map_or(self, Environment::Custom)
+    }
+
+    pub fn base_url(&self) -> Url {
+        match self {
+            Environment::Sandbox => Url::parse(SANDBOX_URL).unwrap(),
+            Environment::Production => Url::parse(PRODUCTION_URL).unwrap(),
+            Environment::Custom(url) => url.clone()
+        }
+    }
+}
+
+impl FromStr for Environment {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sandbox" => Ok(Environment::Sandbox),
+            "production" | "prod" | "live" => Ok(Environment::Production),
+            _ => Url::parse(s)
+                .map(Environment::Custom)
+                .map_err(|_| ApiError::InvalidUrl)
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Environment::Sandbox => write!(f, "sandbox"),
+            Environment::Production => write!(f, "production"),
+            Environment::Custom(url) => write!(f, "{}", url)
+        }
+    }
+}
+
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+pub const DEFAULT_BREAKER_THRESHOLD: u32 = 5;
+pub const DEFAULT_BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// ApiConfig holds the settings shared by every request to Tradier.
+#[derive(Clone)]
+pub struct ApiConfig {
+    pub environment: Environment,
+    pub api_key: Option<String>,
+    /// How long to wait for a connection to be established.
+    pub connect_timeout: Duration,
+    /// How long a whole request may take, from connecting to reading the body.
+    pub timeout: Duration,
+    pub retry: RetryPolicy,
+    /// Consecutive failed requests before the circuit breaker opens.
+    pub breaker_threshold: u32,
+    /// How long the circuit breaker stays open before trying again.
+    pub breaker_cooldown: Duration,
+    /// Whether orders may be placed in production. Previews are always
+    /// allowed; sandbox orders never touch real money.
+    pub allow_live_orders: bool
+}
+
+impl ApiConfig {
+    pub fn new(environment: Environment) -> Self {
+        ApiConfig {
+            environment,
+            api_key: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            timeout: DEFAULT_TIMEOUT,
+            retry: RetryPolicy::default(),
+            breaker_threshold: DEFAULT_BREAKER_THRESHOLD,
+            breaker_cooldown: DEFAULT_BREAKER_COOLDOWN,
+            allow_live_orders: false
+        }
+    }
+
+    pub fn base_url(&self) -> Url {
+        self.environment.base_url()
+    }
+
+    /// Whether orders placed through this config are real: production, or a
+    /// base URL that points at the production host anyway.
+    pub fn is_live(&self) -> bool {
+        match &self.environment {
+            Environment::Production => true,
+            Environment::Sandbox => false,
+            Environment::Custom(url) => url.host_str() == Some(PRODUCTION_HOST)
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        ApiConfig::new(Environment::default())
+    }
+}
+
+// Keep the API key out of logs.
+impl fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiConfig")
+            .field("environment", &self.environment)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("connect_timeout", &self.connect_timeout)
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("breaker_threshold", &self.breaker_threshold)
+            .field("breaker_cooldown", &self.breaker_cooldown)
+            .field("allow_live_orders", &self.allow_live_orders)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environments_parse_by_name_or_url() {
+        assert_eq!("sandbox".parse::<Environment>().unwrap(), Environment::Sandbox);
+        assert_eq!("Production".parse::<Environment>().unwrap(), Environment::Production);
+        assert_eq!("live".parse::<Environment>().unwrap(), Environment::Production);
+        let custom = "http://127.0.0.1:8089/v1".parse::<Environment>().unwrap();
+        assert_eq!(custom, Environment::Custom(Url::parse("http://127.0.0.1:8089/v1").unwrap()));
+        assert!("staging".parse::<Environment>().is_err());
+    }
+
+    #[test]
+    fn each_environment_has_its_base_url() {
+        assert_eq!(Environment::Sandbox.base_url().as_str(), "https://sandbox.tradier.com/v1");
+        assert_eq!(Environment::Production.base_url().as_str(), "https://api.tradier.com/v1");
+    }
+
+    #[test]
+    fn a_base_url_overrides_the_environment() {
+        let url = Url::parse("http://127.0.0.1:8089/v1").unwrap();
+        assert_eq!(Environment::Production.with_base_url(Some(url.clone())), Environment::Custom(url));
+        assert_eq!(Environment::Production.with_base_url(None), Environment::Production);
+    }
+
+    #[test]
+    fn only_production_is_live() {
+        assert!(ApiConfig::new(Environment::Production).is_live());
+        assert!(!ApiConfig::new(Environment::Sandbox).is_live());
+        let mock = Environment::Custom(Url::parse("http://127.0.0.1:8089/v1").unwrap());
+        assert!(!ApiConfig::new(mock).is_live());
+        let sandbox = Environment::Custom(Url::parse("https://sandbox.tradier.com/v1").unwrap());
+        assert!(!ApiConfig::new(sandbox).is_live());
+    }
+
+    #[test]
+    fn a_custom_url_on_the_production_host_is_live() {
+        for url in &["https://api.tradier.com/v1", "https://API.tradier.com/beta/", "http://api.tradier.com:443/v1"] {
+            let custom = Environment::Custom(Url::parse(url).unwrap());
+            assert!(ApiConfig::new(custom).is_live(), "{}", url);
+        }
+    }
+}
@@ -1,43 +1,207 @@
-use chrono::{Datelike, DateTime, Timelike, FixedOffset};
+use chrono::{Datelike, DateTime, Timelike, NaiveDate};
+use chrono_tz::Tz;
 use reqwest::Url;
 
 use crate::api::client::{Result, ApiError};
-
-const BASE_URL: &str = "https://sandbox.tradier.com/v1";
+use crate::data::symbology;
 
 #[derive(Debug, Clone)]
 pub enum ApiEndpoint { 
     Quotes { symbols: Vec<String> },
-    TimeSeries { symbol: String, start_date: DateTime<FixedOffset>, end_date: DateTime<FixedOffset>, interval: u8 }
+    TimeSeries {
+        symbol: String,
+        start_date: DateTime<Tz>,
+        end_date: DateTime<Tz>,
+        interval: Interval,
+        session_filter: SessionFilter
+    },
+    History { symbol: String, interval: HistoryInterval, start: NaiveDate, end: NaiveDate },
+    Calendar { year: i32, month: u32 },
+    Clock,
+    /// Created with POST; the session names the URL to stream from.
+    StreamSession,
+    Search { query: String },
+    Lookup { query: String },
+    OptionExpirations { symbol: String },
+    OptionStrikes { symbol: String, expiration: NaiveDate },
+    OptionChain { symbol: String, expiration: NaiveDate },
+    Profile,
+    Balances { account_id: String },
+    Positions { account_id: String },
+    Orders { account_id: String },
+    AccountHistory { account_id: String },
+    /// Form parameters go in the body; see `api::order::OrderRequest`.
+    PlaceOrder { account_id: String },
+    /// Listed with GET, created with POST.
+    Watchlists,
+    /// Read with GET, replaced with PUT.
+    Watchlist { id: String }
+}
+
+/// Bar size for `markets/timesales`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interval {
+    Tick,
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes
+}
+
+impl Interval {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::Tick => "tick",
+            Interval::OneMinute => "1min",
+            Interval::FiveMinutes => "5min",
+            Interval::FifteenMinutes => "15min"
+        }
+    }
+}
+
+/// Bar size for `markets/history`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryInterval {
+    Daily,
+    Weekly,
+    Monthly
+}
+
+impl HistoryInterval {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryInterval::Daily => "daily",
+            HistoryInterval::Weekly => "weekly",
+            HistoryInterval::Monthly => "monthly"
+        }
+    }
+}
+
+/// Which trades `markets/timesales` returns: the regular session only, or
+/// pre-market and after-hours too.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionFilter {
+    All,
+    Open
+}
+
+impl SessionFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionFilter::All => "all",
+            SessionFilter::Open => "open"
+        }
+    }
 }
     
 pub trait Requestable {
-    fn url(&self) -> Result<Url>;
+    fn url(&self, base_url: &Url) -> Result<Url>;
 }
 
 impl Requestable for ApiEndpoint { 
-    fn url(&self) -> Result<Url> { 
+    fn url(&self, base_url: &Url) -> Result<Url> { 
+        let base_url = base_url.as_str().trim_end_matches('/');
         match self {
             ApiEndpoint::Quotes { symbols } => {
+                let symbols: Vec<String> = symbols.iter().map(|s| symbology::normalize(s)).collect();
                 let symbols = symbols.join(",");
-                let url_str = format!("{}/{}", BASE_URL, "markets/quotes");
+                let url_str = format!("{}/{}", base_url, "markets/quotes");
                 Url::parse_with_params(&url_str, &[("symbols", symbols)])
-                    .map_err(|_| ApiError::ParseError)
-            },
-            ApiEndpoint::TimeSeries { symbol, start_date, end_date, interval } => {
-                let url_str = format!("{}/{}", BASE_URL, "markets/timesales");
-                let symbol = format!("symbol={}", symbol);
-                let interval = format!("interval={}min", interval);
-                let start = format!("start={}", date_to_api_string(start_date));
-                let end = format!("start={}", date_to_api_string(end_date));
-                let url = format!("{}?{}&{}&{}&{}", url_str, symbol, interval, start, end);
-                Url::parse(&url).map_err(|_| ApiError::ParseError)
-            }
+                    .map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::TimeSeries { symbol, start_date, end_date, interval, session_filter } => {
+                let url_str = format!("{}/{}", base_url, "markets/timesales");
+                Url::parse_with_params(&url_str, &[
+                    ("symbol", symbology::normalize(symbol)),
+                    ("interval", interval.as_str().to_string()),
+                    ("start", date_to_api_string(start_date)),
+                    ("end", date_to_api_string(end_date)),
+                    ("session_filter", session_filter.as_str().to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::History { symbol, interval, start, end } => {
+                let url_str = format!("{}/{}", base_url, "markets/history");
+                Url::parse_with_params(&url_str, &[
+                    ("symbol", symbology::normalize(symbol)),
+                    ("interval", interval.as_str().to_string()),
+                    ("start", start.format("%Y-%m-%d").to_string()),
+                    ("end", end.format("%Y-%m-%d").to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::Calendar { year, month } => {
+                let url_str = format!("{}/{}", base_url, "markets/calendar");
+                Url::parse_with_params(&url_str, &[
+                    ("month", format!("{:02}", month)),
+                    ("year", year.to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::Clock => {
+                let url_str = format!("{}/{}", base_url, "markets/clock");
+                Url::parse(&url_str).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::StreamSession => {
+                let url_str = format!("{}/{}", base_url, "markets/events/session");
+                Url::parse(&url_str).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::Search { query } => {
+                let url_str = format!("{}/{}", base_url, "markets/search");
+                Url::parse_with_params(&url_str, &[
+                    ("q", query.to_string()),
+                    ("indexes", "true".to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::Lookup { query } => {
+                let url_str = format!("{}/{}", base_url, "markets/lookup");
+                Url::parse_with_params(&url_str, &[
+                    ("q", query.to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::OptionExpirations { symbol } => {
+                let url_str = format!("{}/{}", base_url, "markets/options/expirations");
+                Url::parse_with_params(&url_str, &[
+                    ("symbol", symbol.to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::OptionStrikes { symbol, expiration } => {
+                let url_str = format!("{}/{}", base_url, "markets/options/strikes");
+                Url::parse_with_params(&url_str, &[
+                    ("symbol", symbol.to_string()),
+                    ("expiration", expiration.format("%Y-%m-%d").to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::OptionChain { symbol, expiration } => {
+                let url_str = format!("{}/{}", base_url, "markets/options/chains");
+                Url::parse_with_params(&url_str, &[
+                    ("symbol", symbol.to_string()),
+                    ("expiration", expiration.format("%Y-%m-%d").to_string()),
+                    ("greeks", "true".to_string())
+                ]).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::Profile => {
+                let url_str = format!("{}/{}", base_url, "user/profile");
+                Url::parse(&url_str).map_err(|_| ApiError::InvalidUrl)
+            },
+            ApiEndpoint::Balances { account_id } => path_url(base_url, &["accounts", account_id, "balances"]),
+            ApiEndpoint::Positions { account_id } => path_url(base_url, &["accounts", account_id, "positions"]),
+            ApiEndpoint::Orders { account_id } => path_url(base_url, &["accounts", account_id, "orders"]),
+            ApiEndpoint::AccountHistory { account_id } => path_url(base_url, &["accounts", account_id, "history"]),
+            ApiEndpoint::PlaceOrder { account_id } => path_url(base_url, &["accounts", account_id, "orders"]),
+            ApiEndpoint::Watchlists => path_url(base_url, &["watchlists"]),
+            ApiEndpoint::Watchlist { id } => path_url(base_url, &["watchlists", id])
         }
     }
 }
 
-fn date_to_api_string(date: &DateTime<FixedOffset>) -> String { 
+fn path_url(base_url: &str, segments: &[&str]) -> Result<Url> {
+    let mut url = Url::parse(&format!("{}/", base_url)).map_err(|_| ApiError::InvalidUrl)?;
+    // Push ids as path segments so they're escaped rather than trusted.
+    url.path_segments_mut()
+        .map_err(|_| ApiError::InvalidUrl)?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+fn date_to_api_string(date: &DateTime<Tz>) -> String { 
     format!(
         "{:04}-{:02}-{:02} {:02}:{:02}", 
         date.year(),
@@ -46,4 +210,109 @@ fn date_to_api_string(date: &DateTime<FixedOffset>) -> String {
         date.hour(),
         date.minute()
     )
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util;
+
+    fn base() -> Url {
+        Url::parse("http://127.0.0.1:8089/v1").unwrap()
+    }
+
+    #[test]
+    fn quotes_join_symbols_under_the_base_path() {
+        let url = ApiEndpoint::Quotes { symbols: vec!["SPY".to_string(), "brk/b".to_string()] }.url(&base()).unwrap();
+        assert_eq!(url.as_str(), "http://127.0.0.1:8089/v1/markets/quotes?symbols=SPY%2CBRK.B");
+    }
+
+    #[test]
+    fn a_trailing_slash_on_the_base_url_is_ignored() {
+        let base = Url::parse("https://sandbox.tradier.com/v1/").unwrap();
+        let url = ApiEndpoint::Clock.url(&base).unwrap();
+        assert_eq!(url.as_str(), "https://sandbox.tradier.com/v1/markets/clock");
+    }
+
+    #[test]
+    fn timesales_sends_exchange_time_and_the_filters() {
+        let date = NaiveDate::from_ymd(2020, 7, 2);
+        let start = util::eastern_datetime(date, chrono::NaiveTime::from_hms(9, 30, 0)).unwrap();
+        let end = util::eastern_datetime(date, chrono::NaiveTime::from_hms(16, 0, 0)).unwrap();
+        let endpoint = ApiEndpoint::TimeSeries {
+            symbol: "SPY".to_string(),
+            start_date: start,
+            end_date: end,
+            interval: Interval::FiveMinutes,
+            session_filter: SessionFilter::Open
+        };
+        let url = endpoint.url(&base()).unwrap();
+        assert_eq!(url.path(), "/v1/markets/timesales");
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(params, vec![
+            ("symbol".to_string(), "SPY".to_string()),
+            ("interval".to_string(), "5min".to_string()),
+            ("start".to_string(), "2020-07-02 09:30".to_string()),
+            ("end".to_string(), "2020-07-02 16:00".to_string()),
+            ("session_filter".to_string(), "open".to_string())
+        ]);
+    }
+
+    #[test]
+    fn history_sends_plain_dates() {
+        let endpoint = ApiEndpoint::History {
+            symbol: "AAPL".to_string(),
+            interval: HistoryInterval::Weekly,
+            start: NaiveDate::from_ymd(2019, 7, 2),
+            end: NaiveDate::from_ymd(2020, 7, 2)
+        };
+        let url = endpoint.url(&base()).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://127.0.0.1:8089/v1/markets/history?symbol=AAPL&interval=weekly&start=2019-07-02&end=2020-07-02"
+        );
+    }
+
+    #[test]
+    fn history_intervals_are_lower_case() {
+        let intervals = [HistoryInterval::Daily, HistoryInterval::Weekly, HistoryInterval::Monthly];
+        let names: Vec<&str> = intervals.iter().map(|i| i.as_str()).collect();
+        assert_eq!(names, vec!["daily", "weekly", "monthly"]);
+        let endpoint = ApiEndpoint::History {
+            symbol: "SPY".to_string(),
+            interval: HistoryInterval::Monthly,
+            start: NaiveDate::from_ymd(2015, 7, 2),
+            end: NaiveDate::from_ymd(2020, 7, 2)
+        };
+        assert!(endpoint.url(&base()).unwrap().as_str().contains("interval=monthly"));
+    }
+
+    #[test]
+    fn option_strikes_send_the_expiration() {
+        let endpoint = ApiEndpoint::OptionStrikes { symbol: "SPY".to_string(), expiration: NaiveDate::from_ymd(2020, 7, 6) };
+        let url = endpoint.url(&base()).unwrap();
+        assert_eq!(url.as_str(), "http://127.0.0.1:8089/v1/markets/options/strikes?symbol=SPY&expiration=2020-07-06");
+    }
+
+    #[test]
+    fn ids_are_escaped_as_path_segments() {
+        let url = ApiEndpoint::Balances { account_id: "VA/../123".to_string() }.url(&base()).unwrap();
+        assert_eq!(url.as_str(), "http://127.0.0.1:8089/v1/accounts/VA%2F..%2F123/balances");
+    }
+
+    #[test]
+    fn api_times_are_exchange_wall_clock_time() {
+        use chrono::{TimeZone, Utc};
+        // 13:30 UTC is 08:30 before the clocks go forward and 09:30 after.
+        let cases = [
+            (Utc.ymd(2020, 3, 6).and_hms(14, 30, 0), "2020-03-06 09:30"),
+            (Utc.ymd(2020, 3, 9).and_hms(13, 30, 0), "2020-03-09 09:30"),
+            (Utc.ymd(2020, 3, 9).and_hms(20, 0, 0), "2020-03-09 16:00"),
+            (Utc.ymd(2020, 10, 30).and_hms(20, 0, 0), "2020-10-30 16:00"),
+            (Utc.ymd(2020, 11, 2).and_hms(21, 0, 0), "2020-11-02 16:00")
+        ];
+        for (instant, expected) in cases.iter() {
+            assert_eq!(date_to_api_string(&instant.with_timezone(&util::eastern())), *expected);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use serde::de::DeserializeOwned;
+
+use crate::api::client::{Result, ApiError};
+use crate::api::endpoint::{HistoryInterval, Interval, SessionFilter};
+use crate::api::order::OrderRequest;
+use crate::api::provider::{AccountProvider, MarketDataProvider};
+use crate::data::{
+    AccountHistory, Balances, Calendar, Clock, Expirations, History, OptionChain, OrderConfirmation, OrderPreview,
+    Orders, Positions, Profile, Quotes, Securities, Strikes, TimeSeries, Watchlist, Watchlists
+};
+
+/// FixtureProvider serves canned Tradier responses from a directory on disk,
+/// so the app can run without network access or an API key. Files are laid
+/// out like the Tradier paths they stand in for, e.g. `markets/quotes.json`.
+/// Nothing is ever written, so orders and watchlist edits fail.
+pub struct FixtureProvider {
+    dir: PathBuf
+}
+
+impl FixtureProvider {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        FixtureProvider { dir: dir.as_ref().to_path_buf() }
+    }
+
+    /// `{endpoint}/{SYMBOL}.json` if there is one, otherwise `{endpoint}.json`.
+    fn symbol_path(&self, endpoint: &str, symbol: &str) -> PathBuf {
+        let path = Path::new(endpoint).join(format!("{}.json", symbol.to_uppercase()));
+        if self.dir.join(&path).exists() {
+            path
+        } else {
+            PathBuf::from(format!("{}.json", endpoint))
+        }
+    }
+
+    fn read<T: DeserializeOwned>(&self, path: &Path) -> Result<T> {
+        let path = self.dir.join(path);
+        let url = path.display().to_string();
+        let s = fs::read_to_string(&path)
+            .map_err(|err| ApiError::Http { status: 404, url: url.clone(), message: Some(err.to_string()), body: String::new() })?;
+        serde_json::from_str(&s).map_err(|err| ApiError::Deserialization {
+            url,
+            line: err.line(),
+            column: err.column(),
+            msg: err.to_string()
+        })
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for FixtureProvider {
+    fn name(&self) -> &str {
+        "Fixtures"
+    }
+
+    // `markets/quotes.json` holds every quote.
+    async fn get_stock_quotes(&self, symbols: Vec<String>) -> Result<Quotes> {
+        let quotes: Quotes = self.read(Path::new("markets/quotes.json"))?;
+        Ok(quotes.retain_symbols(&symbols))
+    }
+
+    // `markets/timesales/{SYMBOL}.json`, falling back to `markets/timesales.json`.
+    async fn get_time_series_data(&self, symbol: String, _start_date: DateTime<Tz>, _end_date: DateTime<Tz>, _interval: Interval, _session_filter: SessionFilter) -> Result<TimeSeries> {
+        self.read(&self.symbol_path("markets/timesales", &symbol))
+    }
+
+    // Daily bars, laid out like time and sales.
+    async fn get_history(&self, symbol: String, _interval: HistoryInterval, start: NaiveDate, end: NaiveDate) -> Result<History> {
+        let history: History = self.read(&self.symbol_path("markets/history", &symbol))?;
+        Ok(history.retain_dates(start, end))
+    }
+
+    // Calendars live in `markets/calendar/{YYYY}-{MM}.json`. Without a
+    // recorded month, answer with an empty calendar so the app
+    // falls back to its own rules instead of logging an error.
+    async fn get_calendar(&self, year: i32, month: u32) -> Result<Calendar> {
+        let path = Path::new("markets/calendar").join(format!("{}-{:02}.json", year, month));
+        if self.dir.join(&path).exists() {
+            self.read(&path)
+        } else {
+            Ok(Calendar::default())
+        }
+    }
+
+    // A recorded clock goes stale at once, so an empty one tells the app to
+    // work it out from the calendar.
+    async fn get_clock(&self) -> Result<Clock> {
+        Ok(Clock::default())
+    }
+
+    // Search and lookup both filter the securities in `markets/lookup.json`.
+    async fn search(&self, query: String) -> Result<Securities> {
+        let securities: Securities = self.read(Path::new("markets/lookup.json"))?;
+        let query = query.to_lowercase();
+        Ok(securities.retain(|m| m.description.to_lowercase().contains(&query)))
+    }
+
+    async fn lookup(&self, query: String) -> Result<Securities> {
+        let securities: Securities = self.read(Path::new("markets/lookup.json"))?;
+        let query = query.to_uppercase();
+        Ok(securities.retain(|m| m.symbol.starts_with(&query)))
+    }
+
+    // Options data is per symbol under `markets/options/`.
+    async fn get_option_expirations(&self, symbol: String) -> Result<Expirations> {
+        self.read(&self.symbol_path("markets/options/expirations", &symbol))
+    }
+
+    async fn get_option_strikes(&self, symbol: String, _expiration: NaiveDate) -> Result<Strikes> {
+        self.read(&self.symbol_path("markets/options/strikes", &symbol))
+    }
+
+    // The recorded chain is served for whichever expiration is asked for.
+    async fn get_option_chain(&self, symbol: String, _expiration: NaiveDate) -> Result<OptionChain> {
+        self.read(&self.symbol_path("markets/options/chains", &symbol))
+    }
+}
+
+#[async_trait]
+impl AccountProvider for FixtureProvider {
+    // Account data is the same whatever the account number.
+    async fn get_profile(&self) -> Result<Profile> {
+        self.read(Path::new("user/profile.json"))
+    }
+
+    async fn get_balances(&self, _account_id: String) -> Result<Balances> {
+        self.read(Path::new("accounts/balances.json"))
+    }
+
+    async fn get_positions(&self, _account_id: String) -> Result<Positions> {
+        self.read(Path::new("accounts/positions.json"))
+    }
+
+    async fn get_orders(&self, _account_id: String) -> Result<Orders> {
+        self.read(Path::new("accounts/orders.json"))
+    }
+
+    async fn get_account_history(&self, _account_id: String) -> Result<AccountHistory> {
+        self.read(Path::new("accounts/history.json"))
+    }
+
+    async fn preview_order(&self, _account_id: String, _order: OrderRequest) -> Result<OrderPreview> {
+        self.read(Path::new("accounts/orders/preview.json"))
+    }
+
+    async fn place_order(&self, _account_id: String, _order: OrderRequest) -> Result<OrderConfirmation> {
+        Err(ApiError::SetUp { msg: "Orders can't be placed from fixtures".to_string() })
+    }
+
+    // Watchlists are listed in `watchlists.json`, and each one's symbols are
+    // in `watchlists/{id}.json`.
+    async fn get_watchlists(&self) -> Result<Watchlists> {
+        self.read(Path::new("watchlists.json"))
+    }
+
+    async fn get_watchlist(&self, id: String) -> Result<Watchlist> {
+        self.read(&Path::new("watchlists").join(format!("{}.json", id)))
+    }
+
+    async fn create_watchlist(&self, _name: String, _symbols: Vec<String>) -> Result<Watchlist> {
+        Err(ApiError::SetUp { msg: "Watchlists can't be changed in fixtures".to_string() })
+    }
+
+    async fn update_watchlist(&self, _id: String, _name: String, _symbols: Vec<String>) -> Result<Watchlist> {
+        Err(ApiError::SetUp { msg: "Watchlists can't be changed in fixtures".to_string() })
+    }
+}
@@ -1,2 +1,17 @@
 pub mod client;
-mod endpoint;
\ No newline at end of file
+pub mod config;
+pub mod error;
+pub mod fixture;
+pub mod order;
+pub mod provider;
+pub mod ratelimit;
+pub mod retry;
+pub mod strategy;
+pub mod stream;
+mod endpoint;
+
+pub use client::TradierClient;
+pub use config::{ApiConfig, Environment};
+pub use endpoint::{HistoryInterval, Interval, SessionFilter};
+pub use fixture::FixtureProvider;
+pub use provider::{AccountProvider, MarketDataProvider};
@@ -0,0 +1,665 @@
+use std::fmt;
+
+use crate::data::symbology;
+
+/// Side of an equity order. Closing a short is `BuyToCover`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+    SellShort,
+    BuyToCover
+}
+
+/// Side of an option order, which also says whether it opens or closes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OptionSide {
+    BuyToOpen,
+    BuyToClose,
+    SellToOpen,
+    SellToClose
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderType {
+    Market,
+    Limit,
+    Stop,
+    StopLimit
+}
+
+/// How long an order stays working.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderDuration {
+    Day,
+    Gtc
+}
+
+impl OrderSide {
+    pub const ALL: [OrderSide; 4] = [OrderSide::Buy, OrderSide::Sell, OrderSide::SellShort, OrderSide::BuyToCover];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+            OrderSide::SellShort => "sell_short",
+            OrderSide::BuyToCover => "buy_to_cover"
+        }
+    }
+}
+
+impl OrderType {
+    pub const ALL: [OrderType; 4] = [OrderType::Market, OrderType::Limit, OrderType::Stop, OrderType::StopLimit];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Market => "market",
+            OrderType::Limit => "limit",
+            OrderType::Stop => "stop",
+            OrderType::StopLimit => "stop_limit"
+        }
+    }
+
+    pub fn needs_price(&self) -> bool {
+        matches!(self, OrderType::Limit | OrderType::StopLimit)
+    }
+
+    pub fn needs_stop(&self) -> bool {
+        matches!(self, OrderType::Stop | OrderType::StopLimit)
+    }
+}
+
+impl OptionSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OptionSide::BuyToOpen => "buy_to_open",
+            OptionSide::BuyToClose => "buy_to_close",
+            OptionSide::SellToOpen => "sell_to_open",
+            OptionSide::SellToClose => "sell_to_close"
+        }
+    }
+
+    pub fn is_buy(&self) -> bool {
+        matches!(self, OptionSide::BuyToOpen | OptionSide::BuyToClose)
+    }
+}
+
+impl OrderDuration {
+    pub const ALL: [OrderDuration; 2] = [OrderDuration::Day, OrderDuration::Gtc];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderDuration::Day => "day",
+            OrderDuration::Gtc => "gtc"
+        }
+    }
+}
+
+/// EquityOrder is a single stock or ETF order.
+#[derive(Debug, Clone)]
+pub struct EquityOrder {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: u32,
+    pub order_type: OrderType,
+    pub duration: OrderDuration,
+    /// Limit price, for limit and stop-limit orders.
+    pub price: Option<f64>,
+    /// Trigger price, for stop and stop-limit orders.
+    pub stop: Option<f64>
+}
+
+/// OptionOrder is a single option order. `option_symbol` is the contract and
+/// `symbol` its underlying.
+#[derive(Debug, Clone)]
+pub struct OptionOrder {
+    pub symbol: String,
+    pub option_symbol: String,
+    pub side: OptionSide,
+    pub quantity: u32,
+    pub order_type: OrderType,
+    pub duration: OrderDuration,
+    pub price: Option<f64>,
+    pub stop: Option<f64>
+}
+
+/// How a multileg order is priced. Debit and credit carry the net limit price
+/// per share.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MultilegPrice {
+    Market,
+    Debit(f64),
+    Credit(f64),
+    Even
+}
+
+impl MultilegPrice {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MultilegPrice::Market => "market",
+            MultilegPrice::Debit(_) => "debit",
+            MultilegPrice::Credit(_) => "credit",
+            MultilegPrice::Even => "even"
+        }
+    }
+
+    pub fn limit(&self) -> Option<f64> {
+        match self {
+            MultilegPrice::Debit(price) | MultilegPrice::Credit(price) => Some(*price),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OptionLeg {
+    pub option_symbol: String,
+    pub side: OptionSide,
+    pub quantity: u32
+}
+
+/// MultilegOrder is a spread: up to four option legs on one underlying,
+/// filled together at a net price.
+#[derive(Debug, Clone)]
+pub struct MultilegOrder {
+    pub symbol: String,
+    pub legs: Vec<OptionLeg>,
+    pub price: MultilegPrice,
+    pub duration: OrderDuration
+}
+
+/// Which conditional order Tradier should build from the legs, in order:
+/// OTO triggers the second when the first fills, OCO cancels one when the
+/// other fills, and OTOCO triggers an OCO pair when the first fills.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    Oto,
+    Oco,
+    Otoco
+}
+
+impl Condition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Condition::Oto => "oto",
+            Condition::Oco => "oco",
+            Condition::Otoco => "otoco"
+        }
+    }
+
+    fn legs(&self) -> usize {
+        match self {
+            Condition::Oto | Condition::Oco => 2,
+            Condition::Otoco => 3
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LegSide {
+    Equity(OrderSide),
+    Option(OptionSide)
+}
+
+impl LegSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LegSide::Equity(side) => side.as_str(),
+            LegSide::Option(side) => side.as_str()
+        }
+    }
+}
+
+/// ConditionalLeg is one order in a conditional group. Option legs name the
+/// contract in `option_symbol` and keep the underlying in `symbol`.
+#[derive(Debug, Clone)]
+pub struct ConditionalLeg {
+    pub symbol: String,
+    pub option_symbol: Option<String>,
+    pub side: LegSide,
+    pub quantity: u32,
+    pub order_type: OrderType,
+    pub price: Option<f64>,
+    pub stop: Option<f64>
+}
+
+#[derive(Debug, Clone)]
+pub struct ConditionalOrder {
+    pub condition: Condition,
+    pub legs: Vec<ConditionalLeg>,
+    pub duration: OrderDuration
+}
+
+/// OrderRequest is an order as sent to `accounts/{id}/orders`.
+#[derive(Debug, Clone)]
+pub enum OrderRequest {
+    Equity(EquityOrder),
+    Option(OptionOrder),
+    Multileg(MultilegOrder),
+    Conditional(ConditionalOrder)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+    NoQuantity,
+    MissingPrice,
+    MissingStop,
+    TooFewLegs,
+    TooManyLegs,
+    WrongLegCount { condition: Condition, expected: usize }
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderError::NoQuantity => write!(f, "Quantity must be at least 1"),
+            OrderError::MissingPrice => write!(f, "This order type needs a limit price"),
+            OrderError::MissingStop => write!(f, "This order type needs a stop price"),
+            OrderError::TooFewLegs => write!(f, "A multileg order needs at least two legs"),
+            OrderError::TooManyLegs => write!(f, "A multileg order can have at most four legs"),
+            OrderError::WrongLegCount { condition, expected } => {
+                write!(f, "An {} order needs {} orders", condition.as_str().to_uppercase(), expected)
+            }
+        }
+    }
+}
+
+fn check_prices(order_type: OrderType, price: Option<f64>, stop: Option<f64>) -> Result<(), OrderError> {
+    if order_type.needs_price() && price.is_none() {
+        return Err(OrderError::MissingPrice);
+    }
+    if order_type.needs_stop() && stop.is_none() {
+        return Err(OrderError::MissingStop);
+    }
+    Ok(())
+}
+
+type Form = Vec<(String, String)>;
+
+fn push(form: &mut Form, key: &str, value: String) {
+    form.push((key.to_string(), value));
+}
+
+fn push_prices(form: &mut Form, suffix: &str, order_type: OrderType, price: Option<f64>, stop: Option<f64>) {
+    if let (true, Some(price)) = (order_type.needs_price(), price) {
+        push(form, &format!("price{}", suffix), format!("{:.2}", price));
+    }
+    if let (true, Some(stop)) = (order_type.needs_stop(), stop) {
+        push(form, &format!("stop{}", suffix), format!("{:.2}", stop));
+    }
+}
+
+impl EquityOrder {
+    pub fn validate(&self) -> Result<(), OrderError> {
+        if self.quantity == 0 {
+            return Err(OrderError::NoQuantity);
+        }
+        check_prices(self.order_type, self.price, self.stop)
+    }
+
+    fn fill_form(&self, form: &mut Form) {
+        push(form, "class", "equity".to_string());
+        push(form, "symbol", symbology::normalize(&self.symbol));
+        push(form, "side", self.side.as_str().to_string());
+        push(form, "quantity", self.quantity.to_string());
+        push(form, "type", self.order_type.as_str().to_string());
+        push(form, "duration", self.duration.as_str().to_string());
+        push_prices(form, "", self.order_type, self.price, self.stop);
+    }
+}
+
+impl OptionOrder {
+    pub fn validate(&self) -> Result<(), OrderError> {
+        if self.quantity == 0 {
+            return Err(OrderError::NoQuantity);
+        }
+        check_prices(self.order_type, self.price, self.stop)
+    }
+
+    fn fill_form(&self, form: &mut Form) {
+        push(form, "class", "option".to_string());
+        push(form, "symbol", symbology::normalize(&self.symbol));
+        push(form, "option_symbol", self.option_symbol.clone());
+        push(form, "side", self.side.as_str().to_string());
+        push(form, "quantity", self.quantity.to_string());
+        push(form, "type", self.order_type.as_str().to_string());
+        push(form, "duration", self.duration.as_str().to_string());
+        push_prices(form, "", self.order_type, self.price, self.stop);
+    }
+}
+
+impl MultilegOrder {
+    pub fn validate(&self) -> Result<(), OrderError> {
+        if self.legs.len() < 2 {
+            return Err(OrderError::TooFewLegs);
+        }
+        if self.legs.len() > 4 {
+            return Err(OrderError::TooManyLegs);
+        }
+        if self.legs.iter().any(|leg| leg.quantity == 0) {
+            return Err(OrderError::NoQuantity);
+        }
+        Ok(())
+    }
+
+    fn fill_form(&self, form: &mut Form) {
+        push(form, "class", "multileg".to_string());
+        push(form, "symbol", symbology::normalize(&self.symbol));
+        push(form, "type", self.price.as_str().to_string());
+        push(form, "duration", self.duration.as_str().to_string());
+        if let Some(price) = self.price.limit() {
+            push(form, "price", format!("{:.2}", price));
+        }
+        for (i, leg) in self.legs.iter().enumerate() {
+            push(form, &format!("option_symbol[{}]", i), leg.option_symbol.clone());
+            push(form, &format!("side[{}]", i), leg.side.as_str().to_string());
+            push(form, &format!("quantity[{}]", i), leg.quantity.to_string());
+        }
+    }
+}
+
+impl ConditionalOrder {
+    pub fn validate(&self) -> Result<(), OrderError> {
+        let expected = self.condition.legs();
+        if self.legs.len() != expected {
+            return Err(OrderError::WrongLegCount { condition: self.condition, expected });
+        }
+        for leg in &self.legs {
+            if leg.quantity == 0 {
+                return Err(OrderError::NoQuantity);
+            }
+            check_prices(leg.order_type, leg.price, leg.stop)?;
+        }
+        Ok(())
+    }
+
+    fn fill_form(&self, form: &mut Form) {
+        push(form, "class", self.condition.as_str().to_string());
+        push(form, "duration", self.duration.as_str().to_string());
+        for (i, leg) in self.legs.iter().enumerate() {
+            let suffix = format!("[{}]", i);
+            push(form, &format!("symbol{}", suffix), symbology::normalize(&leg.symbol));
+            if let Some(option_symbol) = &leg.option_symbol {
+                push(form, &format!("option_symbol{}", suffix), option_symbol.clone());
+            }
+            push(form, &format!("side{}", suffix), leg.side.as_str().to_string());
+            push(form, &format!("quantity{}", suffix), leg.quantity.to_string());
+            push(form, &format!("type{}", suffix), leg.order_type.as_str().to_string());
+            push_prices(form, &suffix, leg.order_type, leg.price, leg.stop);
+        }
+    }
+}
+
+impl OrderRequest {
+    /// Check the order has what it needs before Tradier sees it.
+    pub fn validate(&self) -> Result<(), OrderError> {
+        match self {
+            OrderRequest::Equity(order) => order.validate(),
+            OrderRequest::Option(order) => order.validate(),
+            OrderRequest::Multileg(order) => order.validate(),
+            OrderRequest::Conditional(order) => order.validate()
+        }
+    }
+
+    /// Form parameters for the order, with `preview` set to get Tradier's
+    /// estimate instead of placing it.
+    pub fn form(&self, preview: bool) -> Vec<(String, String)> {
+        let mut form = vec![];
+        match self {
+            OrderRequest::Equity(order) => order.fill_form(&mut form),
+            OrderRequest::Option(order) => order.fill_form(&mut form),
+            OrderRequest::Multileg(order) => order.fill_form(&mut form),
+            OrderRequest::Conditional(order) => order.fill_form(&mut form)
+        }
+        if preview {
+            push(&mut form, "preview", "true".to_string());
+        }
+        form
+    }
+
+    /// A one-line description for the log.
+    pub fn summary(&self) -> String {
+        match self {
+            OrderRequest::Equity(order) => format!("{} {} {} ({})",
+                order.side.as_str(), order.quantity, order.symbol, order.order_type.as_str()),
+            OrderRequest::Option(order) => format!("{} {} {} ({})",
+                order.side.as_str(), order.quantity, order.option_symbol, order.order_type.as_str()),
+            OrderRequest::Multileg(order) => format!("{}-leg {} spread ({})",
+                order.legs.len(), order.symbol, order.price.as_str()),
+            OrderRequest::Conditional(order) => {
+                let legs: Vec<String> = order.legs.iter()
+                    .map(|leg| format!("{} {} {}", leg.side.as_str(), leg.quantity,
+                        leg.option_symbol.as_ref().unwrap_or(&leg.symbol)))
+                    .collect();
+                format!("{} [{}]", order.condition.as_str().to_uppercase(), legs.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn equity(order_type: OrderType, price: Option<f64>, stop: Option<f64>) -> OrderRequest {
+        OrderRequest::Equity(EquityOrder {
+            symbol: "spy".to_string(),
+            side: OrderSide::Buy,
+            quantity: 5,
+            order_type,
+            duration: OrderDuration::Day,
+            price,
+            stop
+        })
+    }
+
+    fn value<'a>(form: &'a [(String, String)], key: &str) -> Option<&'a str> {
+        form.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn preview_is_flagged_only_when_asked() {
+        let order = equity(OrderType::Market, None, None);
+        assert_eq!(value(&order.form(true), "preview"), Some("true"));
+        assert_eq!(value(&order.form(false), "preview"), None);
+    }
+
+    #[test]
+    fn equity_form() {
+        let form = equity(OrderType::Market, None, None).form(false);
+        assert_eq!(form, vec![
+            ("class".to_string(), "equity".to_string()),
+            ("symbol".to_string(), "SPY".to_string()),
+            ("side".to_string(), "buy".to_string()),
+            ("quantity".to_string(), "5".to_string()),
+            ("type".to_string(), "market".to_string()),
+            ("duration".to_string(), "day".to_string())
+        ]);
+    }
+
+    #[test]
+    fn preferred_share_symbols_keep_their_case() {
+        for &symbol in &["ABRpD", "ABR-D", "ABR$D"] {
+            let order = OrderRequest::Equity(EquityOrder {
+                symbol: symbol.to_string(),
+                side: OrderSide::Buy,
+                quantity: 5,
+                order_type: OrderType::Market,
+                duration: OrderDuration::Day,
+                price: None,
+                stop: None
+            });
+            assert_eq!(value(&order.form(false), "symbol"), Some("ABRpD"), "{}", symbol);
+        }
+        let mut order = otoco();
+        for leg in order.legs.iter_mut() {
+            leg.symbol = "ABRpD".to_string();
+        }
+        let form = OrderRequest::Conditional(order).form(false);
+        assert_eq!(value(&form, "symbol[0]"), Some("ABRpD"));
+        assert_eq!(value(&form, "symbol[2]"), Some("ABRpD"));
+    }
+
+    #[test]
+    fn prices_are_sent_only_for_the_types_that_use_them() {
+        let cases = [
+            (OrderType::Market, None, None),
+            (OrderType::Limit, Some("310.50"), None),
+            (OrderType::Stop, None, Some("300.00")),
+            (OrderType::StopLimit, Some("310.50"), Some("300.00"))
+        ];
+        for &(order_type, price, stop) in cases.iter() {
+            let form = equity(order_type, Some(310.5), Some(300.0)).form(true);
+            assert_eq!(value(&form, "price"), price, "{:?}", order_type);
+            assert_eq!(value(&form, "stop"), stop, "{:?}", order_type);
+        }
+    }
+
+    #[test]
+    fn equity_validation() {
+        assert_eq!(equity(OrderType::Market, None, None).validate(), Ok(()));
+        assert_eq!(equity(OrderType::Limit, None, None).validate(), Err(OrderError::MissingPrice));
+        assert_eq!(equity(OrderType::Stop, Some(1.0), None).validate(), Err(OrderError::MissingStop));
+        assert_eq!(equity(OrderType::StopLimit, None, Some(1.0)).validate(), Err(OrderError::MissingPrice));
+        assert_eq!(equity(OrderType::StopLimit, Some(1.0), Some(1.0)).validate(), Ok(()));
+        let mut order = equity(OrderType::Market, None, None);
+        if let OrderRequest::Equity(order) = &mut order {
+            order.quantity = 0;
+        }
+        assert_eq!(order.validate(), Err(OrderError::NoQuantity));
+    }
+
+    #[test]
+    fn option_form_names_the_contract_and_underlying() {
+        let order = OrderRequest::Option(OptionOrder {
+            symbol: "spy".to_string(),
+            option_symbol: "SPY200717C00310000".to_string(),
+            side: OptionSide::SellToClose,
+            quantity: 2,
+            order_type: OrderType::Limit,
+            duration: OrderDuration::Gtc,
+            price: Some(7.5),
+            stop: None
+        });
+        assert_eq!(order.validate(), Ok(()));
+        assert_eq!(order.form(false), vec![
+            ("class".to_string(), "option".to_string()),
+            ("symbol".to_string(), "SPY".to_string()),
+            ("option_symbol".to_string(), "SPY200717C00310000".to_string()),
+            ("side".to_string(), "sell_to_close".to_string()),
+            ("quantity".to_string(), "2".to_string()),
+            ("type".to_string(), "limit".to_string()),
+            ("duration".to_string(), "gtc".to_string()),
+            ("price".to_string(), "7.50".to_string())
+        ]);
+        assert_eq!(order.summary(), "sell_to_close 2 SPY200717C00310000 (limit)");
+    }
+
+    fn keys(form: &[(String, String)]) -> Vec<&str> {
+        form.iter().map(|(key, _)| key.as_str()).collect()
+    }
+
+    fn leg(option_symbol: &str, side: OptionSide) -> OptionLeg {
+        OptionLeg { option_symbol: option_symbol.to_string(), side, quantity: 1 }
+    }
+
+    fn vertical(price: MultilegPrice) -> OrderRequest {
+        OrderRequest::Multileg(MultilegOrder {
+            symbol: "spy".to_string(),
+            legs: vec![
+                leg("SPY200717C00310000", OptionSide::BuyToOpen),
+                leg("SPY200717C00315000", OptionSide::SellToOpen)
+            ],
+            price,
+            duration: OrderDuration::Day
+        })
+    }
+
+    #[test]
+    fn multileg_form_numbers_the_legs() {
+        let form = vertical(MultilegPrice::Debit(1.25)).form(true);
+        assert_eq!(keys(&form), vec![
+            "class", "symbol", "type", "duration", "price",
+            "option_symbol[0]", "side[0]", "quantity[0]",
+            "option_symbol[1]", "side[1]", "quantity[1]",
+            "preview"
+        ]);
+        assert_eq!(value(&form, "class"), Some("multileg"));
+        assert_eq!(value(&form, "type"), Some("debit"));
+        assert_eq!(value(&form, "price"), Some("1.25"));
+        assert_eq!(value(&form, "option_symbol[1]"), Some("SPY200717C00315000"));
+        assert_eq!(value(&form, "side[1]"), Some("sell_to_open"));
+    }
+
+    #[test]
+    fn multileg_price_types() {
+        let form = vertical(MultilegPrice::Credit(0.8)).form(false);
+        assert_eq!((value(&form, "type"), value(&form, "price")), (Some("credit"), Some("0.80")));
+        let form = vertical(MultilegPrice::Even).form(false);
+        assert_eq!((value(&form, "type"), value(&form, "price")), (Some("even"), None));
+        let form = vertical(MultilegPrice::Market).form(false);
+        assert_eq!((value(&form, "type"), value(&form, "price")), (Some("market"), None));
+    }
+
+    #[test]
+    fn multileg_validation() {
+        assert_eq!(vertical(MultilegPrice::Even).validate(), Ok(()));
+        let one_leg = OrderRequest::Multileg(MultilegOrder {
+            symbol: "SPY".to_string(),
+            legs: vec![leg("SPY200717C00310000", OptionSide::BuyToOpen)],
+            price: MultilegPrice::Market,
+            duration: OrderDuration::Day
+        });
+        assert_eq!(one_leg.validate(), Err(OrderError::TooFewLegs));
+    }
+
+    fn bracket_leg(side: OrderSide, order_type: OrderType, price: Option<f64>, stop: Option<f64>) -> ConditionalLeg {
+        ConditionalLeg {
+            symbol: "spy".to_string(),
+            option_symbol: None,
+            side: LegSide::Equity(side),
+            quantity: 10,
+            order_type,
+            price,
+            stop
+        }
+    }
+
+    fn otoco() -> ConditionalOrder {
+        ConditionalOrder {
+            condition: Condition::Otoco,
+            legs: vec![
+                bracket_leg(OrderSide::Buy, OrderType::Limit, Some(310.0), None),
+                bracket_leg(OrderSide::Sell, OrderType::Limit, Some(320.0), None),
+                bracket_leg(OrderSide::Sell, OrderType::Stop, None, Some(300.0))
+            ],
+            duration: OrderDuration::Gtc
+        }
+    }
+
+    #[test]
+    fn otoco_form_prices_each_leg() {
+        let form = OrderRequest::Conditional(otoco()).form(false);
+        assert_eq!(value(&form, "class"), Some("otoco"));
+        assert_eq!(value(&form, "type[0]"), Some("limit"));
+        assert_eq!(value(&form, "price[0]"), Some("310.00"));
+        assert_eq!(value(&form, "price[1]"), Some("320.00"));
+        assert_eq!(value(&form, "type[2]"), Some("stop"));
+        assert_eq!(value(&form, "stop[2]"), Some("300.00"));
+        assert_eq!(value(&form, "price[2]"), None);
+        assert_eq!(value(&form, "stop[0]"), None);
+        assert_eq!(value(&form, "symbol[2]"), Some("SPY"));
+        assert_eq!(value(&form, "option_symbol[0]"), None);
+    }
+
+    #[test]
+    fn conditional_validation() {
+        assert_eq!(OrderRequest::Conditional(otoco()).validate(), Ok(()));
+        let mut order = otoco();
+        order.legs.pop();
+        assert_eq!(order.validate(), Err(OrderError::WrongLegCount { condition: Condition::Otoco, expected: 3 }));
+        let mut order = otoco();
+        order.legs[2].stop = None;
+        assert_eq!(order.validate(), Err(OrderError::MissingStop));
+    }
+}
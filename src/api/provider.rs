@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::api::client::Result;
+use crate::api::endpoint::{HistoryInterval, Interval, SessionFilter};
+use crate::api::order::OrderRequest;
+use crate::api::ratelimit::RateBudget;
+use crate::api::retry::BreakerState;
+use crate::api::stream::EventStream;
+use crate::api::client::ApiError;
+use crate::data::{
+    AccountHistory, Balances, Calendar, Clock, Expirations, History, OptionChain, OrderConfirmation, OrderPreview,
+    Orders, Positions, Profile, Quotes, Securities, Strikes, TimeSeries, Watchlist, Watchlists
+};
+
+/// MarketDataProvider is a source of market data for the app. Tradier is the
+/// default implementation, but anything that can answer these calls
+/// (recorded fixtures, another vendor) can be plugged into `App`.
+#[async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    /// A short name for the provider, shown in the log pane.
+    fn name(&self) -> &str;
+
+    /// The request budget left in the current rate limit window, if the
+    /// provider has one.
+    fn rate_budget(&self) -> Option<RateBudget> {
+        None
+    }
+
+    /// The state of the provider's circuit breaker, if it has one.
+    fn breaker_state(&self) -> Option<BreakerState> {
+        None
+    }
+
+    async fn get_stock_quotes(&self, symbols: Vec<String>) -> Result<Quotes>;
+
+    async fn get_time_series_data(
+        &self,
+        symbol: String,
+        start_date: DateTime<Tz>,
+        end_date: DateTime<Tz>,
+        interval: Interval,
+        session_filter: SessionFilter
+    ) -> Result<TimeSeries>;
+
+    async fn get_history(
+        &self,
+        symbol: String,
+        interval: HistoryInterval,
+        start: NaiveDate,
+        end: NaiveDate
+    ) -> Result<History>;
+
+    /// The exchange calendar for one month.
+    async fn get_calendar(&self, year: i32, month: u32) -> Result<Calendar>;
+
+    /// The exchange's current session state and when it next changes.
+    async fn get_clock(&self) -> Result<Clock>;
+
+    /// Securities whose company name matches `query`, indices included.
+    async fn search(&self, query: String) -> Result<Securities>;
+
+    /// Securities whose symbol starts with `query`.
+    async fn lookup(&self, query: String) -> Result<Securities>;
+
+    /// Expiration dates of the listed options on `symbol`.
+    async fn get_option_expirations(&self, symbol: String) -> Result<Expirations>;
+
+    /// Strike prices listed for `symbol` options expiring on `expiration`.
+    async fn get_option_strikes(&self, symbol: String, expiration: NaiveDate) -> Result<Strikes>;
+
+    /// Every call and put on `symbol` expiring on `expiration`, with greeks.
+    async fn get_option_chain(&self, symbol: String, expiration: NaiveDate) -> Result<OptionChain>;
+
+    /// Live quote, trade and summary events for `symbols`, for as long as
+    /// the connection lasts.
+    async fn stream_events(&self, _symbols: Vec<String>) -> Result<EventStream> {
+        Err(ApiError::SetUp { msg: format!("{} doesn't stream quotes", self.name()) })
+    }
+}
+
+/// AccountProvider is a brokerage account's holdings and activity. It's kept
+/// apart from `MarketDataProvider` so a market data source doesn't need to
+/// know about accounts.
+#[async_trait]
+pub trait AccountProvider: Send + Sync {
+    /// The user and the accounts they can trade in.
+    async fn get_profile(&self) -> Result<Profile>;
+
+    async fn get_balances(&self, account_id: String) -> Result<Balances>;
+
+    async fn get_positions(&self, account_id: String) -> Result<Positions>;
+
+    async fn get_orders(&self, account_id: String) -> Result<Orders>;
+
+    async fn get_account_history(&self, account_id: String) -> Result<AccountHistory>;
+
+    /// Whether orders are placed with real money.
+    fn is_live(&self) -> bool {
+        false
+    }
+
+    /// Tradier's estimated cost for an order, without placing it.
+    async fn preview_order(&self, account_id: String, order: OrderRequest) -> Result<OrderPreview>;
+
+    async fn place_order(&self, account_id: String, order: OrderRequest) -> Result<OrderConfirmation>;
+
+    /// The user's watchlists, without their symbols.
+    async fn get_watchlists(&self) -> Result<Watchlists>;
+
+    async fn get_watchlist(&self, id: String) -> Result<Watchlist>;
+
+    async fn create_watchlist(&self, name: String, symbols: Vec<String>) -> Result<Watchlist>;
+
+    /// Replace a watchlist's name and symbols.
+    async fn update_watchlist(&self, id: String, name: String, symbols: Vec<String>) -> Result<Watchlist>;
+}
@@ -0,0 +1,196 @@
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use tokio::sync::Mutex;
+
+const ALLOWED_HEADER: &str = "x-ratelimit-allowed";
+const USED_HEADER: &str = "x-ratelimit-used";
+const AVAILABLE_HEADER: &str = "x-ratelimit-available";
+const EXPIRY_HEADER: &str = "x-ratelimit-expiry";
+
+/// Below this share of the allowance left, requests are spread out over the
+/// rest of the window instead of being sent back to back.
+const LOW_WATER_FRACTION: f64 = 0.1;
+
+/// RateBudget is Tradier's view of how many requests we have left, as last
+/// reported in the `X-Ratelimit-*` response headers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateBudget {
+    pub allowed: u32,
+    pub used: u32,
+    pub available: u32,
+    pub expiry: DateTime<Utc>
+}
+
+impl RateBudget {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let get = |name: &str| -> Option<u64> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        };
+        Some(RateBudget {
+            allowed: get(ALLOWED_HEADER)? as u32,
+            used: get(USED_HEADER)? as u32,
+            available: get(AVAILABLE_HEADER)? as u32,
+            expiry: Utc.timestamp_millis(get(EXPIRY_HEADER)? as i64)
+        })
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expiry
+    }
+
+    /// How long the next request should wait, if at all.
+    fn delay(&self, now: DateTime<Utc>) -> Option<Duration> {
+        if self.is_expired(now) {
+            return None;
+        }
+        let remaining = (self.expiry - now).to_std().unwrap_or_default();
+        let low_water = (self.allowed as f64 * LOW_WATER_FRACTION).ceil() as u32;
+        if self.available == 0 {
+            Some(remaining)
+        } else if self.available <= low_water {
+            Some(remaining / (self.available + 1))
+        } else {
+            None
+        }
+    }
+}
+
+/// RateLimiter paces requests to stay inside Tradier's budget. It's shared
+/// by every request from a client; callers wait their turn in `acquire`
+/// before sending and report the response headers back with `update`.
+pub struct RateLimiter {
+    budget: StdMutex<Option<RateBudget>>,
+    queue: Mutex<()>
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter {
+            budget: StdMutex::new(None),
+            queue: Mutex::new(())
+        }
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        RateLimiter::default()
+    }
+
+    /// Wait until the budget allows another request. The turn is held while
+    /// waiting, on purpose: when the budget runs low, requests then go out
+    /// one per interval instead of all waking together at the end of it.
+    pub async fn acquire(&self) {
+        let _turn = self.queue.lock().await;
+        let delay = {
+            let mut budget = self.budget.lock().unwrap();
+            match budget.as_mut() {
+                Some(b) => {
+                    let delay = b.delay(Utc::now());
+                    b.available = b.available.saturating_sub(1);
+                    b.used += 1;
+                    delay
+                },
+                None => None
+            }
+        };
+        if let Some(delay) = delay {
+            tokio::time::delay_for(delay).await;
+        }
+    }
+
+    pub fn update(&self, headers: &HeaderMap) {
+        if let Some(budget) = RateBudget::from_headers(headers) {
+            *self.budget.lock().unwrap() = Some(budget);
+        }
+    }
+
+    /// The current budget, or `None` if we haven't heard from Tradier yet or
+    /// the last window has ended.
+    pub fn budget(&self) -> Option<RateBudget> {
+        let budget = *self.budget.lock().unwrap();
+        budget.filter(|b| !b.is_expired(Utc::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(allowed: &str, used: &str, available: &str, expiry: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for &(name, value) in &[(ALLOWED_HEADER, allowed), (USED_HEADER, used), (AVAILABLE_HEADER, available), (EXPIRY_HEADER, expiry)] {
+            headers.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn budget(allowed: u32, available: u32, expiry: DateTime<Utc>) -> RateBudget {
+        RateBudget { allowed, used: allowed - available, available, expiry }
+    }
+
+    #[test]
+    fn budgets_come_from_the_headers() {
+        let budget = RateBudget::from_headers(&headers("120", "20", " 100 ", "1593614400000")).unwrap();
+        assert_eq!(budget, RateBudget { allowed: 120, used: 20, available: 100, expiry: Utc.timestamp(1593614400, 0) });
+    }
+
+    #[test]
+    fn missing_or_garbled_headers_are_no_budget() {
+        assert_eq!(RateBudget::from_headers(&HeaderMap::new()), None);
+        let mut partial = headers("120", "20", "100", "1593614400000");
+        partial.remove(EXPIRY_HEADER);
+        assert_eq!(RateBudget::from_headers(&partial), None);
+        assert_eq!(RateBudget::from_headers(&headers("120", "twenty", "100", "1593614400000")), None);
+        assert_eq!(RateBudget::from_headers(&headers("120", "20", "-1", "1593614400000")), None);
+    }
+
+    #[test]
+    fn plenty_left_means_no_wait() {
+        let now = Utc.timestamp(1593614400, 0);
+        assert_eq!(budget(120, 100, now + chrono::Duration::seconds(60)).delay(now), None);
+    }
+
+    #[test]
+    fn nothing_left_waits_for_the_reset() {
+        let now = Utc.timestamp(1593614400, 0);
+        assert_eq!(budget(120, 0, now + chrono::Duration::seconds(30)).delay(now), Some(Duration::from_secs(30)));
+        // Once the window is over the old numbers don't count.
+        assert_eq!(budget(120, 0, now).delay(now), None);
+    }
+
+    #[test]
+    fn a_low_budget_is_spread_over_the_window() {
+        let now = Utc.timestamp(1593614400, 0);
+        let expiry = now + chrono::Duration::seconds(60);
+        // 10% of 120 is 12: at 12 left, the 60s are split 13 ways.
+        assert_eq!(budget(120, 12, expiry).delay(now), Some(Duration::from_secs(60) / 13));
+        assert_eq!(budget(120, 2, expiry).delay(now), Some(Duration::from_secs(20)));
+        assert_eq!(budget(120, 13, expiry).delay(now), None);
+    }
+
+    #[tokio::test]
+    async fn acquiring_spends_the_budget() {
+        let limiter = RateLimiter::new();
+        limiter.acquire().await;
+        assert_eq!(limiter.budget(), None);
+
+        let expiry = (Utc::now() + chrono::Duration::minutes(1)).timestamp_millis().to_string();
+        limiter.update(&headers("120", "20", "100", &expiry));
+        limiter.acquire().await;
+        limiter.acquire().await;
+        let budget = limiter.budget().unwrap();
+        assert_eq!((budget.used, budget.available), (22, 98));
+    }
+
+    #[test]
+    fn an_expired_budget_is_forgotten() {
+        let limiter = RateLimiter::new();
+        limiter.update(&headers("120", "120", "0", "1593614400000"));
+        assert_eq!(limiter.budget(), None);
+    }
+}
@@ -0,0 +1,209 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+
+use crate::api::error::{ApiError, Result};
+
+/// RetryPolicy decides how often and how patiently a failed request is tried
+/// again. Delays grow exponentially from `base_delay` up to `max_delay`, with
+/// full jitter so concurrent callers don't retry in lockstep.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(8)
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before retry number `attempt`, counting from zero.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let ceiling = self.base_delay
+            .checked_mul(2u32.saturating_pow(attempt))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        let millis = ceiling.as_millis() as u64;
+        if millis == 0 {
+            return ceiling;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(0, millis + 1))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BreakerState {
+    /// Requests flow normally.
+    Closed,
+    /// Too many recent failures; requests fail fast until `until`.
+    Open { until: DateTime<Utc> },
+    /// The cooldown has passed and requests are let through on trial. One
+    /// more failure reopens the breaker; a success closes it.
+    HalfOpen
+}
+
+/// CircuitBreaker stops sending requests to an API that keeps failing, and
+/// lets them through again after a cooldown to find out whether it has
+/// recovered.
+pub struct CircuitBreaker {
+    state: Mutex<(BreakerState, u32)>,
+    failure_threshold: u32,
+    cooldown: Duration
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            state: Mutex::new((BreakerState::Closed, 0)),
+            failure_threshold: failure_threshold.max(1),
+            cooldown
+        }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.state.lock().unwrap().0
+    }
+
+    /// Fail fast if the breaker is open; otherwise let the request through.
+    pub fn check(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        match state.0 {
+            BreakerState::Open { until } if Utc::now() < until => {
+                Err(ApiError::CircuitOpen { until })
+            },
+            BreakerState::Open { .. } => {
+                state.0 = BreakerState::HalfOpen;
+                Ok(())
+            },
+            _ => Ok(())
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.state.lock().unwrap() = (BreakerState::Closed, 0);
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.1 += 1;
+        if state.0 == BreakerState::HalfOpen || state.1 >= self.failure_threshold {
+            let cooldown = chrono::Duration::from_std(self.cooldown).unwrap_or_else(|_| chrono::Duration::zero());
+            state.0 = BreakerState::Open { until: Utc::now() + cooldown };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::error::ErrorKind;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { max_retries: 3, base_delay: Duration::from_millis(100), max_delay: Duration::from_millis(1000) }
+    }
+
+    #[test]
+    fn delays_stay_under_a_doubling_ceiling() {
+        let policy = policy();
+        for &(attempt, ceiling) in &[(0, 100), (1, 200), (2, 400), (3, 800), (4, 1000), (40, 1000)] {
+            for _ in 0..200 {
+                assert!(policy.delay(attempt) <= Duration::from_millis(ceiling), "attempt {}", attempt);
+            }
+        }
+    }
+
+    #[test]
+    fn delays_are_jittered_across_the_range() {
+        let delays: Vec<Duration> = (0..500).map(|_| policy().delay(3)).collect();
+        assert!(delays.iter().any(|d| *d < Duration::from_millis(200)));
+        assert!(delays.iter().any(|d| *d > Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn a_zero_base_delay_never_waits() {
+        let policy = RetryPolicy { base_delay: Duration::from_millis(0), ..policy() };
+        assert_eq!(policy.delay(5), Duration::from_millis(0));
+    }
+
+    #[test]
+    fn the_breaker_opens_at_the_threshold() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert!(breaker.check().is_ok());
+        breaker.record_failure();
+        assert!(matches!(breaker.state(), BreakerState::Open { .. }));
+        assert_eq!(breaker.check().unwrap_err().kind(), ErrorKind::Unavailable);
+    }
+
+    #[test]
+    fn a_success_resets_the_count() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn the_breaker_half_opens_after_the_cooldown() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(0));
+        breaker.record_failure();
+        assert!(matches!(breaker.state(), BreakerState::Open { .. }));
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+    }
+
+    #[test]
+    fn a_failure_while_half_open_reopens() {
+        let breaker = CircuitBreaker::new(5, Duration::from_secs(0));
+        for _ in 0..5 {
+            breaker.record_failure();
+        }
+        breaker.check().unwrap();
+        breaker.record_failure();
+        assert!(matches!(breaker.state(), BreakerState::Open { .. }));
+    }
+
+    #[test]
+    fn a_success_while_half_open_closes() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(0));
+        breaker.record_failure();
+        breaker.check().unwrap();
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        // The count started over, so the threshold applies afresh.
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(0));
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker.check().unwrap();
+        breaker.record_success();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        let http = |status| ApiError::Http { status, url: String::new(), message: None, body: String::new() };
+        let network = |timeout| ApiError::Network { url: String::new(), msg: String::new(), timeout };
+        for error in &[network(false), network(true), http(429), http(500), http(503)] {
+            assert!(error.is_transient(), "{:?}", error);
+        }
+        let deserialization = ApiError::Deserialization { url: String::new(), line: 1, column: 1, msg: String::new() };
+        let open = ApiError::CircuitOpen { until: Utc::now() };
+        for error in &[http(400), http(401), http(404), deserialization, open, ApiError::InvalidUrl] {
+            assert!(!error.is_transient(), "{:?}", error);
+        }
+    }
+}
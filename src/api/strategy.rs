@@ -0,0 +1,327 @@
+use crate::api::order::{MultilegOrder, MultilegPrice, OptionLeg, OptionSide, OrderDuration};
+use crate::data::{OptionChain, OptionType};
+use crate::data::chain::OptionContract;
+
+/// The spreads the options builder can put together from a chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrategyKind {
+    CallVertical,
+    PutVertical,
+    Straddle,
+    Strangle,
+    IronCondor
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 5] = [
+        StrategyKind::CallVertical,
+        StrategyKind::PutVertical,
+        StrategyKind::Straddle,
+        StrategyKind::Strangle,
+        StrategyKind::IronCondor
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StrategyKind::CallVertical => "Call vertical",
+            StrategyKind::PutVertical => "Put vertical",
+            StrategyKind::Straddle => "Straddle",
+            StrategyKind::Strangle => "Strangle",
+            StrategyKind::IronCondor => "Iron condor"
+        }
+    }
+}
+
+/// What a position can lose at expiration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Risk {
+    Limited(f64),
+    Unlimited
+}
+
+#[derive(Debug, Clone)]
+pub struct StrategyLeg {
+    pub contract: OptionContract,
+    pub side: OptionSide
+}
+
+impl StrategyLeg {
+    /// Midpoint of the market, or the last trade when there isn't one.
+    pub fn mid(&self) -> Option<f64> {
+        match (self.contract.bid, self.contract.ask) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+            _ => self.contract.last
+        }
+    }
+
+    fn sign(&self) -> f64 {
+        if self.side.is_buy() { 1.0 } else { -1.0 }
+    }
+
+    fn value_at(&self, underlying: f64) -> f64 {
+        let intrinsic = match self.contract.option_type {
+            OptionType::Call => (underlying - self.contract.strike).max(0.0),
+            OptionType::Put => (self.contract.strike - underlying).max(0.0)
+        };
+        self.sign() * intrinsic
+    }
+}
+
+/// Strategy is one of each leg of a spread, all opening. Buying a strategy
+/// takes the long side: the debit vertical, the long straddle or strangle,
+/// the long iron condor. Selling takes the other side of every leg.
+#[derive(Debug, Clone)]
+pub struct Strategy {
+    pub kind: StrategyKind,
+    pub buy: bool,
+    pub legs: Vec<StrategyLeg>
+}
+
+impl Strategy {
+    /// Build `kind` around the strike at `center` in `chain.by_strike()`,
+    /// with the wings `width` strikes apart. None when the chain doesn't
+    /// have the strikes or contracts it needs.
+    pub fn build(kind: StrategyKind, chain: &OptionChain, center: usize, width: usize, buy: bool) -> Option<Strategy> {
+        let strikes = chain.by_strike();
+        let width = width.max(1) as isize;
+        let center = center as isize;
+        let contract = |offset: isize, option_type: OptionType| -> Option<OptionContract> {
+            let index = center + offset;
+            if index < 0 {
+                return None;
+            }
+            let (_, call, put) = strikes.get(index as usize)?;
+            match option_type {
+                OptionType::Call => call.cloned(),
+                OptionType::Put => put.cloned()
+            }
+        };
+        let (open, close) = if buy {
+            (OptionSide::BuyToOpen, OptionSide::SellToOpen)
+        } else {
+            (OptionSide::SellToOpen, OptionSide::BuyToOpen)
+        };
+        let legs = match kind {
+            StrategyKind::CallVertical => vec![
+                (contract(0, OptionType::Call)?, open),
+                (contract(width, OptionType::Call)?, close)
+            ],
+            StrategyKind::PutVertical => vec![
+                (contract(0, OptionType::Put)?, open),
+                (contract(-width, OptionType::Put)?, close)
+            ],
+            StrategyKind::Straddle => vec![
+                (contract(0, OptionType::Put)?, open),
+                (contract(0, OptionType::Call)?, open)
+            ],
+            StrategyKind::Strangle => vec![
+                (contract(-width, OptionType::Put)?, open),
+                (contract(width, OptionType::Call)?, open)
+            ],
+            StrategyKind::IronCondor => vec![
+                (contract(-2 * width, OptionType::Put)?, close),
+                (contract(-width, OptionType::Put)?, open),
+                (contract(width, OptionType::Call)?, open),
+                (contract(2 * width, OptionType::Call)?, close)
+            ]
+        };
+        let legs = legs.into_iter().map(|(contract, side)| StrategyLeg { contract, side }).collect();
+        Some(Strategy { kind, buy, legs })
+    }
+
+    pub fn underlying(&self) -> &str {
+        &self.legs[0].contract.underlying
+    }
+
+    /// Shares per contract, which is the same for every leg.
+    pub fn multiplier(&self) -> f64 {
+        self.legs[0].contract.contract_size.unwrap_or(100) as f64
+    }
+
+    /// Net price per share at the midpoints: positive is a debit paid,
+    /// negative a credit received. None if a leg has no price.
+    pub fn net_price(&self) -> Option<f64> {
+        self.legs.iter().map(|leg| leg.mid().map(|mid| leg.sign() * mid)).sum()
+    }
+
+    /// Whether opening the strategy costs money. Without prices, the long
+    /// side is taken to be the debit.
+    pub fn is_debit(&self) -> bool {
+        self.net_price().map_or(self.buy, |net| net > 0.0)
+    }
+
+    /// The most one spread can lose at expiration, net of what it cost or
+    /// took in. Payoffs are straight lines between strikes, so checking
+    /// zero, each strike and the slope past the highest strike is enough.
+    pub fn max_risk(&self) -> Option<Risk> {
+        let net = self.net_price()?;
+        let calls_beyond: f64 = self.legs.iter()
+            .filter(|leg| leg.contract.option_type == OptionType::Call)
+            .map(|leg| leg.sign())
+            .sum();
+        if calls_beyond < 0.0 {
+            return Some(Risk::Unlimited);
+        }
+        let payoff = |underlying: f64| self.legs.iter().map(|leg| leg.value_at(underlying)).sum::<f64>() - net;
+        let worst = self.legs.iter()
+            .map(|leg| payoff(leg.contract.strike))
+            .fold(payoff(0.0), f64::min);
+        Some(Risk::Limited((-worst).max(0.0) * self.multiplier()))
+    }
+
+    /// The order for `quantity` spreads. A limit is the net price per share,
+    /// as a debit or credit depending on which way the strategy is priced.
+    pub fn order(&self, quantity: u32, limit: Option<f64>, duration: OrderDuration) -> MultilegOrder {
+        let price = match limit {
+            None => MultilegPrice::Market,
+            Some(limit) if limit.abs() < f64::EPSILON => MultilegPrice::Even,
+            Some(limit) if self.is_debit() => MultilegPrice::Debit(limit),
+            Some(limit) => MultilegPrice::Credit(limit)
+        };
+        let legs = self.legs.iter().map(|leg| OptionLeg {
+            option_symbol: leg.contract.symbol.clone(),
+            side: leg.side,
+            quantity
+        }).collect();
+        MultilegOrder { symbol: self.underlying().to_string(), legs, price, duration }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const STRIKES: [f64; 5] = [90.0, 95.0, 100.0, 105.0, 110.0];
+    const CALL_MIDS: [f64; 5] = [10.5, 6.5, 3.0, 1.0, 0.5];
+
+    /// Five strikes around an underlying at 100, puts priced as the mirror
+    /// of the calls. Markets are a quarter wide, so midpoints are exact.
+    fn chain() -> OptionChain {
+        let mut contracts = vec![];
+        for (i, strike) in STRIKES.iter().enumerate() {
+            for &(option_type, code, mid) in &[("call", "C", CALL_MIDS[i]), ("put", "P", CALL_MIDS[4 - i])] {
+                contracts.push(json!({
+                    "symbol": format!("XYZ200717{}{:08}", code, (*strike * 1000.0) as u32),
+                    "underlying": "XYZ",
+                    "strike": strike,
+                    "expiration_date": "2020-07-17",
+                    "option_type": option_type,
+                    "bid": mid - 0.25,
+                    "ask": mid + 0.25,
+                    "contract_size": 100
+                }));
+            }
+        }
+        serde_json::from_value(json!({ "options": { "option": contracts } })).unwrap()
+    }
+
+    fn build(kind: StrategyKind, buy: bool) -> Strategy {
+        Strategy::build(kind, &chain(), 2, 1, buy).unwrap()
+    }
+
+    fn legs(strategy: &Strategy) -> Vec<(OptionSide, &str)> {
+        strategy.legs.iter().map(|leg| (leg.side, leg.contract.symbol.as_str())).collect()
+    }
+
+    #[test]
+    fn long_call_vertical_risks_the_debit() {
+        let strategy = build(StrategyKind::CallVertical, true);
+        assert_eq!(legs(&strategy), vec![
+            (OptionSide::BuyToOpen, "XYZ200717C00100000"),
+            (OptionSide::SellToOpen, "XYZ200717C00105000")
+        ]);
+        assert_eq!(strategy.net_price(), Some(2.0));
+        assert!(strategy.is_debit());
+        assert_eq!(strategy.max_risk(), Some(Risk::Limited(200.0)));
+    }
+
+    #[test]
+    fn short_call_vertical_risks_the_width_less_the_credit() {
+        let strategy = build(StrategyKind::CallVertical, false);
+        assert_eq!(strategy.net_price(), Some(-2.0));
+        assert!(!strategy.is_debit());
+        assert_eq!(strategy.max_risk(), Some(Risk::Limited(300.0)));
+    }
+
+    #[test]
+    fn put_vertical_goes_down_the_chain() {
+        let strategy = build(StrategyKind::PutVertical, true);
+        assert_eq!(legs(&strategy), vec![
+            (OptionSide::BuyToOpen, "XYZ200717P00100000"),
+            (OptionSide::SellToOpen, "XYZ200717P00095000")
+        ]);
+        assert_eq!(strategy.max_risk(), Some(Risk::Limited(200.0)));
+    }
+
+    #[test]
+    fn long_straddle_risks_the_debit() {
+        let strategy = build(StrategyKind::Straddle, true);
+        assert_eq!(strategy.net_price(), Some(6.0));
+        assert_eq!(strategy.max_risk(), Some(Risk::Limited(600.0)));
+    }
+
+    #[test]
+    fn short_strangle_is_unlimited() {
+        let strategy = build(StrategyKind::Strangle, false);
+        assert_eq!(legs(&strategy), vec![
+            (OptionSide::SellToOpen, "XYZ200717P00095000"),
+            (OptionSide::SellToOpen, "XYZ200717C00105000")
+        ]);
+        assert_eq!(strategy.net_price(), Some(-2.0));
+        assert_eq!(strategy.max_risk(), Some(Risk::Unlimited));
+    }
+
+    #[test]
+    fn long_iron_condor_risks_the_debit() {
+        let strategy = build(StrategyKind::IronCondor, true);
+        assert_eq!(legs(&strategy), vec![
+            (OptionSide::SellToOpen, "XYZ200717P00090000"),
+            (OptionSide::BuyToOpen, "XYZ200717P00095000"),
+            (OptionSide::BuyToOpen, "XYZ200717C00105000"),
+            (OptionSide::SellToOpen, "XYZ200717C00110000")
+        ]);
+        assert_eq!(strategy.net_price(), Some(1.0));
+        assert_eq!(strategy.max_risk(), Some(Risk::Limited(100.0)));
+    }
+
+    #[test]
+    fn short_iron_condor_risks_a_wing_less_the_credit() {
+        let strategy = build(StrategyKind::IronCondor, false);
+        assert_eq!(strategy.net_price(), Some(-1.0));
+        assert_eq!(strategy.max_risk(), Some(Risk::Limited(400.0)));
+    }
+
+    #[test]
+    fn build_needs_every_strike_on_the_chain() {
+        let chain = chain();
+        assert!(Strategy::build(StrategyKind::PutVertical, &chain, 0, 1, true).is_none());
+        assert!(Strategy::build(StrategyKind::CallVertical, &chain, 4, 1, true).is_none());
+        assert!(Strategy::build(StrategyKind::IronCondor, &chain, 1, 1, true).is_none());
+        assert!(Strategy::build(StrategyKind::IronCondor, &chain, 2, 2, true).is_none());
+        assert!(Strategy::build(StrategyKind::Straddle, &chain, 5, 1, true).is_none());
+        assert!(Strategy::build(StrategyKind::Strangle, &chain, 2, 2, true).is_some());
+    }
+
+    #[test]
+    fn missing_prices_leave_risk_unknown() {
+        let mut strategy = build(StrategyKind::CallVertical, true);
+        strategy.legs[1].contract.bid = None;
+        assert_eq!(strategy.net_price(), None);
+        assert_eq!(strategy.max_risk(), None);
+        assert!(strategy.is_debit());
+    }
+
+    #[test]
+    fn orders_are_priced_the_way_the_strategy_is() {
+        let long = build(StrategyKind::CallVertical, true);
+        let short = build(StrategyKind::CallVertical, false);
+        assert_eq!(long.order(1, Some(2.0), OrderDuration::Day).price, MultilegPrice::Debit(2.0));
+        assert_eq!(short.order(1, Some(2.0), OrderDuration::Day).price, MultilegPrice::Credit(2.0));
+        assert_eq!(short.order(1, Some(0.0), OrderDuration::Day).price, MultilegPrice::Even);
+        let order = long.order(3, None, OrderDuration::Gtc);
+        assert_eq!(order.price, MultilegPrice::Market);
+        assert_eq!(order.symbol, "XYZ");
+        assert!(order.legs.iter().all(|leg| leg.quantity == 3));
+    }
+}
@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use reqwest::{Response, Url};
+
+use crate::api::error::{ApiError, Result};
+use crate::api::retry::RetryPolicy;
+use crate::data::stream::StreamEvent;
+
+/// EventStream is a live feed of market events. It ends when the server
+/// closes the connection; a failed read is its last item.
+pub type EventStream = BoxStream<'static, Result<StreamEvent>>;
+
+/// Read `response` as newline-delimited JSON events.
+pub fn events(response: Response, url: Url) -> EventStream {
+    let read_url = url.clone();
+    let chunks = stream::unfold(Some(response), |response| async move {
+        let mut response = response?;
+        match response.chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk.to_vec()), Some(response))),
+            Ok(None) => None,
+            Err(err) => Some((Err(err), None))
+        }
+    });
+    frame(chunks.map(move |chunk| chunk.map_err(|err| ApiError::from_reqwest(&read_url, err))), url)
+}
+
+/// Split `chunks` into lines and read each one as an event. Lines can span
+/// chunks, and blank ones are skipped. A line that isn't an event is an
+/// error item, but the stream goes on; a failed read ends it.
+pub fn frame<S>(chunks: S, url: Url) -> EventStream
+where
+    S: Stream<Item = Result<Vec<u8>>> + Send + 'static
+{
+    let state = Some((chunks.boxed(), Vec::new(), url));
+    stream::unfold(state, |state| async move {
+        let (mut chunks, mut buffer, url) = state?;
+        loop {
+            if let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let event = serde_json::from_str(line.trim()).map_err(|err| ApiError::from_serde(&url, err));
+                return Some((event, Some((chunks, buffer, url))));
+            }
+            match chunks.next().await {
+                Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => return Some((Err(err), None)),
+                None => return None
+            }
+        }
+    }).fuse().boxed()
+}
+
+/// Reconnect paces reconnecting to a stream. Delays back off while
+/// connections keep failing, and start over once one delivers events.
+#[derive(Debug, Clone)]
+pub struct Reconnect {
+    policy: RetryPolicy,
+    attempt: u32
+}
+
+impl Reconnect {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Reconnect { policy, attempt: 0 }
+    }
+
+    /// How long to wait before the next connection, after one that
+    /// `received` events or didn't.
+    pub fn delay(&mut self, received: bool) -> Duration {
+        if received {
+            self.attempt = 0;
+        }
+        let delay = self.policy.delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    /// Reconnections since a connection last delivered events.
+    pub fn attempts(&self) -> u32 {
+        self.attempt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::error::ErrorKind;
+
+    fn url() -> Url {
+        Url::parse("http://127.0.0.1:8089/v1/markets/events").unwrap()
+    }
+
+    fn chunks(chunks: &[&str]) -> Vec<Result<Vec<u8>>> {
+        chunks.iter().map(|c| Ok(c.as_bytes().to_vec())).collect()
+    }
+
+    async fn collect(chunks: Vec<Result<Vec<u8>>>) -> Vec<Result<StreamEvent>> {
+        frame(stream::iter(chunks), url()).collect().await
+    }
+
+    fn symbols(events: &[Result<StreamEvent>]) -> Vec<Option<String>> {
+        events.iter().map(|e| e.as_ref().ok().and_then(|e| e.symbol()).map(str::to_string)).collect()
+    }
+
+    #[tokio::test]
+    async fn lines_can_span_chunks() {
+        let events = collect(chunks(&[
+            r#"{"type":"trade","symbol":"SP"#,
+            r#"Y","last":"310.50"}"#,
+            "\n{\"type\":\"quote\",\"symbol\":\"AAPL\"}\n{\"type\":\"summ",
+            "ary\",\"symbol\":\"MSFT\"}\n"
+        ])).await;
+        assert_eq!(symbols(&events), vec![Some("SPY".to_string()), Some("AAPL".to_string()), Some("MSFT".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn blank_lines_are_skipped() {
+        let events = collect(chunks(&["\n\r\n", "{\"type\":\"trade\",\"symbol\":\"SPY\"}\n", "  \n\n"])).await;
+        assert_eq!(events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_bad_line_is_an_error_and_the_stream_goes_on() {
+        let events = collect(chunks(&[
+            "{\"type\":\"trade\",\"symbol\":\"SPY\"}\n",
+            "{\"type\":\"trade\",\"sym\n",
+            "{\"type\":\"quote\",\"symbol\":\"AAPL\"}\n"
+        ])).await;
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1].as_ref().unwrap_err().kind(), ErrorKind::Deserialization);
+        assert_eq!(symbols(&events)[2], Some("AAPL".to_string()));
+    }
+
+    #[tokio::test]
+    async fn a_failed_read_ends_the_stream() {
+        let failure = ApiError::Network { url: url().to_string(), msg: "reset".to_string(), timeout: false };
+        let mut input = chunks(&["{\"type\":\"trade\",\"symbol\":\"SPY\"}\n{\"type\":"]);
+        input.push(Err(failure));
+        input.extend(chunks(&["\"quote\",\"symbol\":\"AAPL\"}\n"]));
+        let events = collect(input).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].as_ref().unwrap_err().kind(), ErrorKind::Network);
+    }
+
+    #[tokio::test]
+    async fn a_partial_last_line_is_dropped() {
+        let events = collect(chunks(&["{\"type\":\"trade\",\"symbol\":\"SPY\"}\n{\"type\":\"quote\""])).await;
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn reconnects_back_off_until_events_arrive() {
+        let policy = RetryPolicy { max_retries: 0, base_delay: Duration::from_secs(1), max_delay: Duration::from_secs(60) };
+        let mut reconnect = Reconnect::new(policy);
+        for expected in 1..=3 {
+            reconnect.delay(false);
+            assert_eq!(reconnect.attempts(), expected);
+        }
+        // Full jitter, so only the ceiling is certain: 1s, then doubling.
+        assert!(reconnect.delay(false) <= Duration::from_secs(8));
+        assert!(reconnect.delay(true) <= Duration::from_secs(1));
+        assert_eq!(reconnect.attempts(), 1);
+        for _ in 0..100 {
+            assert!(reconnect.delay(false) <= Duration::from_secs(60));
+        }
+    }
+}
@@ -0,0 +1,464 @@
+// A stand-in for the Tradier market data API. It serves the canned responses
+// in `fixtures/` so stonks_cli can be run end to end without network access:
+//
+//     cargo run --bin mock_tradier -- --port 8089
+//     TRADIER_BASE_URL=http://127.0.0.1:8089/v1 TRADIER_API_KEY=mock cargo run
+//
+// Streaming sessions are served too: prices random-walk from the fixture
+// quotes, and `--stream-drop-after` hangs up on streams to exercise reconnects.
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use hyper::body::Bytes;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, HOST};
+use hyper::service::{make_service_fn, service_fn};
+use rand::Rng;
+use reqwest::Url;
+use serde_json::{json, Value};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mock_tradier", about = "Serve canned Tradier responses for stonks_cli.")]
+struct Opt {
+    /// Port to listen on.
+    #[structopt(long, default_value = "8089")]
+    port: u16,
+
+    /// Directory holding the canned responses.
+    #[structopt(long, parse(from_os_str), default_value = "fixtures")]
+    fixtures: PathBuf,
+
+    /// Close quote streams after sending this many events, summaries
+    /// included. Streams stay open when 0.
+    #[structopt(long, default_value = "0")]
+    stream_drop_after: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let opt = Opt::from_args();
+    let addr = SocketAddr::from(([127, 0, 0, 1], opt.port));
+    let fixtures = Arc::new(opt.fixtures);
+    STREAM_DROP_AFTER.store(opt.stream_drop_after, Ordering::SeqCst);
+
+    let make_svc = make_service_fn(move |_conn| {
+        let fixtures = Arc::clone(&fixtures);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let fixtures = Arc::clone(&fixtures);
+                async move { Ok::<_, Infallible>(route(&fixtures, req).await) }
+            }))
+        }
+    });
+
+    // Port 0 picks a free port, so say which one it was.
+    let server = Server::bind(&addr).serve(make_svc);
+    println!("Mock Tradier listening on http://{}/v1", server.local_addr());
+    server.await?;
+    Ok(())
+}
+
+async fn route(fixtures: &Path, req: Request<Body>) -> Response<Body> {
+    let authorized = req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| h.starts_with("Bearer "));
+    if !authorized {
+        return respond(StatusCode::UNAUTHORIZED, "Invalid Access Token".to_string());
+    }
+
+    let params = query_params(&req);
+    let path = req.uri().path().to_string();
+    if let Some(resource) = account_resource(&path) {
+        if req.method() == Method::GET {
+            return fixture(fixtures, &format!("accounts/{}", resource));
+        }
+        if req.method() == Method::POST && resource == "orders" {
+            let body = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+            return order(fixtures, &form_params(&body));
+        }
+    }
+    if path == "/v1/watchlists" || path.starts_with("/v1/watchlists/") {
+        let id = path.trim_start_matches("/v1/watchlists").trim_start_matches('/').to_string();
+        let method = req.method().clone();
+        let body = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+        return watchlists(fixtures, &method, &id, &form_params(&body));
+    }
+    if req.method() == Method::POST && path == "/v1/markets/events/session" {
+        return stream_session(&req);
+    }
+    if req.method() == Method::POST && path == "/v1/markets/events" {
+        let body = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+        return stream_events(fixtures, &form_params(&body));
+    }
+    match (req.method(), path.as_str()) {
+        (&Method::GET, "/v1/markets/quotes") => quotes(fixtures, &params),
+        (&Method::GET, "/v1/markets/timesales") => by_symbol(fixtures, "markets/timesales", &params),
+        (&Method::GET, "/v1/markets/history") => by_symbol(fixtures, "markets/history", &params),
+        (&Method::GET, "/v1/markets/calendar") => calendar(fixtures, &params),
+        // No recorded clock is ever current; the app works it out itself.
+        (&Method::GET, "/v1/markets/clock") => respond(StatusCode::OK, json!({ "clock": null }).to_string()),
+        (&Method::GET, "/v1/markets/search") => securities(fixtures, &params, "description"),
+        (&Method::GET, "/v1/markets/lookup") => securities(fixtures, &params, "symbol"),
+        (&Method::GET, "/v1/markets/options/expirations") => by_symbol(fixtures, "markets/options/expirations", &params),
+        (&Method::GET, "/v1/markets/options/strikes") => by_symbol(fixtures, "markets/options/strikes", &params),
+        (&Method::GET, "/v1/markets/options/chains") => by_symbol(fixtures, "markets/options/chains", &params),
+        (&Method::GET, "/v1/user/profile") => fixture(fixtures, "user/profile"),
+        _ => respond(StatusCode::NOT_FOUND, json!({
+            "fault": { "faultstring": "Resource not found" }
+        }).to_string())
+    }
+}
+
+fn quotes(fixtures: &Path, params: &HashMap<String, String>) -> Response<Body> {
+    let all: Value = match read_fixture(&fixtures.join("markets/quotes.json")) {
+        Some(v) => v,
+        None => return respond(StatusCode::INTERNAL_SERVER_ERROR, "Missing quotes fixture".to_string())
+    };
+    let requested: Vec<String> = params.get("symbols")
+        .map(|s| s.split(',').map(|s| s.trim().to_uppercase()).collect())
+        .unwrap_or_default();
+    let quotes: Vec<Value> = all["quotes"]["quote"].as_array()
+        .map(|quotes| quotes.iter()
+            .filter(|q| q["symbol"].as_str().is_some_and(|s| requested.iter().any(|r| r == s)))
+            .cloned()
+            .collect())
+        .unwrap_or_default();
+    let unmatched: Vec<&String> = requested.iter()
+        .filter(|r| !quotes.iter().any(|q| q["symbol"].as_str() == Some(r.as_str())))
+        .collect();
+
+    let mut body = json!({ "quotes": { "quote": quotes } });
+    if !unmatched.is_empty() {
+        body["quotes"]["unmatched_symbols"] = json!({ "symbol": unmatched });
+    }
+    respond(StatusCode::OK, body.to_string())
+}
+
+/// Serve `{endpoint}/{SYMBOL}.json`, falling back to `{endpoint}.json`.
+fn by_symbol(fixtures: &Path, endpoint: &str, params: &HashMap<String, String>) -> Response<Body> {
+    let symbol = match params.get("symbol") {
+        Some(symbol) => symbol.to_uppercase(),
+        None => return respond(StatusCode::BAD_REQUEST, json!({
+            "errors": { "error": ["Missing parameter: symbol"] }
+        }).to_string())
+    };
+    let path = fixtures.join(endpoint).join(format!("{}.json", symbol));
+    let path = if path.exists() { path } else { fixtures.join(format!("{}.json", endpoint)) };
+    match read_fixture(&path) {
+        Some(body) => respond(StatusCode::OK, body.to_string()),
+        None => respond(StatusCode::INTERNAL_SERVER_ERROR, format!("Missing {} fixture", endpoint))
+    }
+}
+
+/// Filter `markets/lookup.json` to the securities whose `field` contains the
+/// `q` parameter. Like Tradier, nothing matching comes back as null.
+fn securities(fixtures: &Path, params: &HashMap<String, String>, field: &str) -> Response<Body> {
+    let all: Value = match read_fixture(&fixtures.join("markets/lookup.json")) {
+        Some(v) => v,
+        None => return respond(StatusCode::INTERNAL_SERVER_ERROR, "Missing lookup fixture".to_string())
+    };
+    let query = params.get("q").map(|q| q.to_lowercase()).unwrap_or_default();
+    let matches: Vec<Value> = all["securities"]["security"].as_array()
+        .map(|securities| securities.iter()
+            .filter(|s| s[field].as_str().is_some_and(|v| v.to_lowercase().contains(&query)))
+            .cloned()
+            .collect())
+        .unwrap_or_default();
+    let body = if matches.is_empty() {
+        json!({ "securities": null })
+    } else {
+        json!({ "securities": { "security": matches } })
+    };
+    respond(StatusCode::OK, body.to_string())
+}
+
+/// The resource in `/v1/accounts/{id}/{resource}`. Every account id gets the
+/// same fixtures.
+fn account_resource(path: &str) -> Option<&str> {
+    let mut segments = path.trim_start_matches("/v1/accounts/").splitn(2, '/');
+    let (_id, resource) = (segments.next()?, segments.next()?);
+    let known = ["balances", "positions", "orders", "history"];
+    if path.starts_with("/v1/accounts/") && known.contains(&resource) {
+        Some(resource)
+    } else {
+        None
+    }
+}
+
+fn fixture(fixtures: &Path, name: &str) -> Response<Body> {
+    match read_fixture(&fixtures.join(format!("{}.json", name))) {
+        Some(body) => respond(StatusCode::OK, body.to_string()),
+        None => respond(StatusCode::INTERNAL_SERVER_ERROR, format!("Missing {} fixture", name))
+    }
+}
+
+static NEXT_ORDER_ID: AtomicU64 = AtomicU64::new(300000);
+
+/// Preview an order by pricing it at the limit price or the fixture quote's
+/// last price, or pretend to place it. Multileg orders are priced at their
+/// net limit, and conditional orders by their first leg.
+fn order(fixtures: &Path, form: &HashMap<String, String>) -> Response<Body> {
+    let class = form.get("class").map_or("equity", String::as_str);
+    // Conditional orders index every field, multileg orders the leg fields.
+    let field = |key: &str| form.get(key).or_else(|| form.get(&format!("{}[0]", key)));
+    let symbol = field("symbol").cloned().unwrap_or_default().to_uppercase();
+    let quantity: f64 = field("quantity").and_then(|q| q.parse().ok()).unwrap_or(0.0);
+    if symbol.is_empty() || quantity <= 0.0 {
+        return respond(StatusCode::BAD_REQUEST, json!({
+            "errors": { "error": "Invalid order: symbol and a positive quantity are required" }
+        }).to_string());
+    }
+    if form.get("preview").map(String::as_str) != Some("true") {
+        let id = NEXT_ORDER_ID.fetch_add(1, Ordering::SeqCst);
+        return respond(StatusCode::OK, json!({ "order": { "id": id, "status": "ok" } }).to_string());
+    }
+    let last = read_fixture(&fixtures.join("markets/quotes.json"))
+        .and_then(|all| all["quotes"]["quote"].as_array()
+            .and_then(|quotes| quotes.iter().find(|q| q["symbol"] == json!(symbol)))
+            .and_then(|q| q["last"].as_f64()));
+    let price: Option<f64> = field("price").and_then(|p| p.parse().ok());
+    let cost = match class {
+        "multileg" => {
+            let net = price.unwrap_or(0.0) * quantity * 100.0;
+            if form.get("type").map(String::as_str) == Some("credit") { -net } else { net }
+        },
+        _ => {
+            let multiplier = if field("option_symbol").is_some() { 100.0 } else { 1.0 };
+            price.or(last).unwrap_or(0.0) * quantity * multiplier
+        }
+    };
+    let cost = (cost * 100.0).round() / 100.0;
+    respond(StatusCode::OK, json!({
+        "order": {
+            "status": "ok",
+            "commission": 0.0,
+            "cost": cost,
+            "fees": 0.0,
+            "symbol": symbol,
+            "quantity": quantity,
+            "side": field("side"),
+            "type": field("type"),
+            "duration": form.get("duration"),
+            "result": true,
+            "order_cost": cost,
+            "margin_change": cost.abs() / 2.0,
+            "extended_hours": false,
+            "class": class,
+            "strategy": class,
+            "day_trades": 0
+        }
+    }).to_string())
+}
+
+/// Watchlists by id, as (name, symbols). They start out as the fixtures in
+/// `watchlists/` and edits last as long as the server does.
+type Watchlists = BTreeMap<String, (String, Vec<String>)>;
+
+static WATCHLISTS: Mutex<Option<Watchlists>> = Mutex::new(None);
+
+fn watchlists(fixtures: &Path, method: &Method, id: &str, form: &HashMap<String, String>) -> Response<Body> {
+    let mut store = WATCHLISTS.lock().unwrap();
+    let store = store.get_or_insert_with(|| seed_watchlists(fixtures));
+    let symbols = || form.get("symbols").map_or(vec![], |s| {
+        s.split(',').map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()).collect()
+    });
+    match (method, id) {
+        (&Method::GET, "") => {
+            let summaries: Vec<Value> = store.iter()
+                .map(|(id, (name, _))| json!({ "name": name, "id": id }))
+                .collect();
+            let body = if summaries.is_empty() {
+                json!({ "watchlists": null })
+            } else {
+                json!({ "watchlists": { "watchlist": summaries } })
+            };
+            respond(StatusCode::OK, body.to_string())
+        },
+        (&Method::POST, "") => {
+            let name = form.get("name").cloned().unwrap_or_default();
+            let id: String = name.to_lowercase().chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            if id.is_empty() || store.contains_key(&id) {
+                return respond(StatusCode::BAD_REQUEST, json!({
+                    "errors": { "error": "A watchlist needs a new, non-empty name" }
+                }).to_string());
+            }
+            store.insert(id.clone(), (name, symbols()));
+            watchlist_response(&id, &store[&id])
+        },
+        (&Method::GET, id) if store.contains_key(id) => watchlist_response(id, &store[id]),
+        (&Method::PUT, id) if store.contains_key(id) => {
+            let name = form.get("name").cloned().unwrap_or_else(|| store[id].0.clone());
+            store.insert(id.to_string(), (name, symbols()));
+            watchlist_response(id, &store[id])
+        },
+        _ => respond(StatusCode::NOT_FOUND, json!({
+            "fault": { "faultstring": "Resource not found" }
+        }).to_string())
+    }
+}
+
+fn seed_watchlists(fixtures: &Path) -> Watchlists {
+    let entries = fs::read_dir(fixtures.join("watchlists")).map(|dir| dir.flatten().collect()).unwrap_or_else(|_| vec![]);
+    entries.iter()
+        .filter_map(|entry| read_fixture(&entry.path()))
+        .filter_map(|fixture| {
+            let watchlist = &fixture["watchlist"];
+            let items = &watchlist["items"]["item"];
+            let items = items.as_array().cloned().unwrap_or_else(|| vec![items.clone()]);
+            let symbols = items.iter().filter_map(|i| i["symbol"].as_str()).map(String::from).collect();
+            Some((watchlist["id"].as_str()?.to_string(), (watchlist["name"].as_str()?.to_string(), symbols)))
+        })
+        .collect()
+}
+
+fn watchlist_response(id: &str, (name, symbols): &(String, Vec<String>)) -> Response<Body> {
+    let items: Vec<Value> = symbols.iter().map(|s| json!({ "symbol": s, "id": s.to_lowercase() })).collect();
+    let items = if items.is_empty() { json!(null) } else { json!({ "item": items }) };
+    respond(StatusCode::OK, json!({
+        "watchlist": { "name": name, "id": id, "items": items }
+    }).to_string())
+}
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+static STREAM_DROP_AFTER: AtomicU64 = AtomicU64::new(0);
+
+/// Hand out a session pointing back at this server, wherever the client
+/// reached it.
+fn stream_session(req: &Request<Body>) -> Response<Body> {
+    let host = req.headers().get(HOST).and_then(|h| h.to_str().ok()).unwrap_or("127.0.0.1:8089");
+    let id = NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst);
+    respond(StatusCode::OK, json!({
+        "stream": { "url": format!("http://{}/v1/markets/events", host), "sessionid": format!("mock-{}", id) }
+    }).to_string())
+}
+
+/// Stream a summary for each requested symbol, then quotes and trades that
+/// random-walk from the fixture quote's last price. Numbers are strings, as
+/// they are from Tradier.
+fn stream_events(fixtures: &Path, form: &HashMap<String, String>) -> Response<Body> {
+    if !form.get("sessionid").is_some_and(|id| id.starts_with("mock-")) {
+        return respond(StatusCode::BAD_REQUEST, json!({
+            "error": "Invalid session"
+        }).to_string());
+    }
+    let requested: Vec<String> = form.get("symbols")
+        .map(|s| s.split(',').map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let all = read_fixture(&fixtures.join("markets/quotes.json")).unwrap_or_default();
+    let mut symbols: Vec<(String, f64, f64, f64)> = all["quotes"]["quote"].as_array()
+        .map(|quotes| quotes.iter()
+            .filter_map(|q| {
+                let symbol = q["symbol"].as_str()?.to_string();
+                if !requested.contains(&symbol) {
+                    return None;
+                }
+                let last = q["last"].as_f64()?;
+                let open = q["open"].as_f64().unwrap_or(last);
+                let prev_close = q["prevclose"].as_f64().unwrap_or(last);
+                Some((symbol, last, open, prev_close))
+            })
+            .collect())
+        .unwrap_or_default();
+    let drop_after = STREAM_DROP_AFTER.load(Ordering::SeqCst);
+
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        let line = |event: Value| Bytes::from(format!("{}\n", event));
+        let mut sent: u64 = 0;
+        let done = |sent: u64| drop_after != 0 && sent >= drop_after;
+        for (symbol, _, open, prev_close) in &symbols {
+            let summary = json!({
+                "type": "summary",
+                "symbol": symbol,
+                "open": format!("{:.2}", open),
+                "prevClose": format!("{:.2}", prev_close)
+            });
+            if done(sent) || sender.send_data(line(summary)).await.is_err() {
+                return;
+            }
+            sent += 1;
+        }
+        let mut cvol: u64 = 0;
+        while !symbols.is_empty() && !done(sent) {
+            tokio::time::delay_for(std::time::Duration::from_millis(250)).await;
+            let event = {
+                let mut rng = rand::thread_rng();
+                let index = rng.gen_range(0, symbols.len());
+                let (symbol, last, _, _) = &mut symbols[index];
+                *last = (*last * (1.0 + rng.gen_range(-0.001, 0.001))).max(0.01);
+                cvol += rng.gen_range(1, 50) * 100;
+                if rng.gen_bool(0.5) {
+                    json!({
+                        "type": "quote",
+                        "symbol": symbol,
+                        "bid": format!("{:.2}", *last - 0.01),
+                        "bidsz": rng.gen_range(1, 20).to_string(),
+                        "ask": format!("{:.2}", *last + 0.01),
+                        "asksz": rng.gen_range(1, 20).to_string()
+                    })
+                } else {
+                    json!({
+                        "type": "trade",
+                        "symbol": symbol,
+                        "last": format!("{:.2}", last),
+                        "cvol": cvol.to_string()
+                    })
+                }
+            };
+            if sender.send_data(line(event)).await.is_err() {
+                return;
+            }
+            sent += 1;
+        }
+    });
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body)
+        .unwrap()
+}
+
+fn form_params(body: &[u8]) -> HashMap<String, String> {
+    let url = format!("http://localhost/?{}", String::from_utf8_lossy(body));
+    Url::parse(&url)
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+fn calendar(fixtures: &Path, params: &HashMap<String, String>) -> Response<Body> {
+    let month: u32 = params.get("month").and_then(|m| m.parse().ok()).unwrap_or(1);
+    let year = params.get("year").cloned().unwrap_or_default();
+    let path = fixtures.join(format!("markets/calendar/{}-{:02}.json", year, month));
+    let body = read_fixture(&path).unwrap_or_else(|| json!({ "calendar": null }));
+    respond(StatusCode::OK, body.to_string())
+}
+
+fn read_fixture(path: &Path) -> Option<Value> {
+    let s = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&s).ok()
+}
+
+fn query_params(req: &Request<Body>) -> HashMap<String, String> {
+    let url = format!("http://localhost{}", req.uri());
+    Url::parse(&url)
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+fn respond(status: StatusCode, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
@@ -0,0 +1,22 @@
+use crate::cli::ui::StatefulList;
+use crate::data::{self, AccountHistory};
+use crate::data::account::{Balances, Position, Profile};
+
+/// AccountState is the brokerage account view. `account_number` is the
+/// account to show; without one, the first account on the profile is used.
+#[derive(Default)]
+pub struct AccountState {
+    pub account_number: Option<String>,
+    pub profile: Option<Profile>,
+    pub balances: Option<Balances>,
+    pub positions: StatefulList<Position>,
+    pub orders: Option<data::Orders>,
+    pub history: Option<AccountHistory>,
+    pub loading: bool
+}
+
+impl AccountState {
+    pub fn selected_position(&self) -> Option<&Position> {
+        self.positions.state.selected().map(|i| &self.positions.list[i])
+    }
+}
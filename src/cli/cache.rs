@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
+use chrono::NaiveDate;
+
 use crate::data;
+use crate::cli::ui::ChartRange;
 
 pub type QuoteCache = HashMap<String, data::Quote>;
-pub type GraphCache = HashMap<String, data::TimeSeries>;
\ No newline at end of file
+pub type GraphCache = HashMap<String, data::TimeSeries>;
+pub type HistoryCache = HashMap<(String, ChartRange), data::History>;
+pub type ChainCache = HashMap<(String, NaiveDate), data::OptionChain>;
\ No newline at end of file
@@ -0,0 +1,105 @@
+use chrono::NaiveDate;
+
+use crate::api::strategy::{Strategy, StrategyKind};
+use crate::cli::ui::StatefulList;
+use crate::data::OptionChain;
+
+/// ChainState is the options view: the underlying, the expirations it
+/// has listed options for, and the spread being built, if any.
+#[derive(Default)]
+pub struct ChainState {
+    pub symbol: String,
+    pub expirations: StatefulList<NaiveDate>,
+    pub builder: Option<StrategyBuilder>
+}
+
+/// StrategyBuilder is where a spread sits on the chain. It starts at the
+/// money and follows the user from there.
+#[derive(Debug, Clone, Copy)]
+pub struct StrategyBuilder {
+    pub kind: StrategyKind,
+    pub buy: bool,
+    /// Index into the chain's strikes, or None for at the money.
+    pub center: Option<usize>,
+    /// Strikes between the legs.
+    pub width: usize
+}
+
+impl ChainState {
+    pub fn new(symbol: String) -> Self {
+        ChainState { symbol, expirations: StatefulList::default(), builder: None }
+    }
+
+    pub fn selected_expiration(&self) -> Option<NaiveDate> {
+        self.expirations.state.selected().map(|i| self.expirations.list[i])
+    }
+
+    /// The builder's spread on `chain`, with `last` for the underlying
+    /// placing it at the money.
+    pub fn strategy(&self, chain: &OptionChain, last: Option<f64>) -> Option<Strategy> {
+        let builder = self.builder?;
+        let center = builder.center.or_else(|| at_the_money(chain, last))?;
+        Strategy::build(builder.kind, chain, center, builder.width, builder.buy)
+    }
+}
+
+impl StrategyBuilder {
+    pub fn new() -> Self {
+        StrategyBuilder { kind: StrategyKind::CallVertical, buy: true, center: None, width: 1 }
+    }
+
+    /// Move the center strike by `offset`, staying on the chain.
+    pub fn shift(&mut self, chain: &OptionChain, last: Option<f64>, offset: isize) {
+        let strikes = chain.by_strike().len();
+        if strikes == 0 {
+            return;
+        }
+        let center = match self.center.or_else(|| at_the_money(chain, last)) {
+            Some(center) => center as isize,
+            None => return
+        };
+        self.center = Some((center + offset).max(0).min(strikes as isize - 1) as usize);
+    }
+}
+
+/// Index of the strike nearest `last` in `chain.by_strike()`.
+pub fn at_the_money(chain: &OptionChain, last: Option<f64>) -> Option<usize> {
+    let last = last?;
+    let strikes = chain.by_strike();
+    (0..strikes.len()).min_by(|&a, &b| {
+        let (da, db) = ((strikes[a].0 - last).abs(), (strikes[b].0 - last).abs());
+        da.partial_cmp(&db).unwrap()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> OptionChain {
+        serde_json::from_str(include_str!("../../fixtures/markets/options/chains.json")).unwrap()
+    }
+
+    #[test]
+    fn shifting_stays_on_the_chain() {
+        let chain = chain();
+        let mut builder = StrategyBuilder::new();
+        builder.shift(&chain, Some(311.0), 1);
+        assert_eq!(builder.center, Some(5));
+        builder.shift(&chain, None, -20);
+        assert_eq!(builder.center, Some(0));
+        builder.shift(&chain, None, 20);
+        assert_eq!(builder.center, Some(10));
+    }
+
+    #[test]
+    fn shifting_an_empty_chain_does_nothing() {
+        let empty: OptionChain = serde_json::from_str(r#"{"options":null}"#).unwrap();
+        let mut builder = StrategyBuilder { center: Some(3), ..StrategyBuilder::new() };
+        builder.shift(&empty, Some(311.0), 1);
+        assert_eq!(builder.center, Some(3));
+        let mut builder = StrategyBuilder::new();
+        builder.shift(&empty, Some(311.0), 1);
+        assert_eq!(builder.center, None);
+    }
+}
@@ -22,9 +22,7 @@ pub enum Event<I> {
 /// type is handled in its own thread and returned to a common `Receiver`
 pub struct Events {
     rx: mpsc::Receiver<Event<Key>>,
-    input_handle: task::JoinHandle<()>,
     ignore_exit_key: Arc<AtomicBool>,
-    tick_handle: task::JoinHandle<()>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -42,45 +40,45 @@ impl Default for Config {
     }
 }
 
+impl Default for Events {
+    fn default() -> Events {
+        Events::with_config(Config::default())
+    }
+}
+
 impl Events {
     pub fn new() -> Events {
-        Events::with_config(Config::default())
+        Events::default()
     }
 
     pub fn with_config(config: Config) -> Events {
         let (mut tx, rx) = mpsc::channel(100);
         let ignore_exit_key = Arc::new(AtomicBool::new(false));
-        let input_handle = {
+        {
             let mut tx = tx.clone();
             let ignore_exit_key = ignore_exit_key.clone();
             task::spawn(async move {
                 let stdin = io::stdin();
-                for evt in stdin.keys() {
-                    if let Ok(key) = evt {
-                        if let Err(err) = tx.send(Event::Input(key)).await {
-                            eprintln!("{}", err);
-                            return;
-                        }
-                        if !ignore_exit_key.load(Ordering::Relaxed) && key == config.exit_key {
-                            return;
-                        }
+                for key in stdin.keys().flatten() {
+                    if let Err(err) = tx.send(Event::Input(key)).await {
+                        eprintln!("{}", err);
+                        return;
+                    }
+                    if !ignore_exit_key.load(Ordering::Relaxed) && key == config.exit_key {
+                        return;
                     }
                 }
-            })
-        };
-        let tick_handle = {
-            task::spawn(async move {
-                loop {
-                    let _ = tx.send(Event::Tick).await;
-                    tokio::time::delay_for(config.tick_rate).await;
-                }
-            })
-        };
+            });
+        }
+        task::spawn(async move {
+            loop {
+                let _ = tx.send(Event::Tick).await;
+                tokio::time::delay_for(config.tick_rate).await;
+            }
+        });
         Events {
             rx,
             ignore_exit_key,
-            input_handle,
-            tick_handle,
         }
     }
 
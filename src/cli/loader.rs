@@ -1,76 +1,68 @@
 use std::{io, io::Write};
 use std::{fs, fs::File};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-use chrono::{Date, Datelike, FixedOffset, TimeZone};
+use chrono::{Date, TimeZone};
+use chrono_tz::Tz;
 use ftp::FtpStream;
 
 use crate::util;
 use crate::cli::CliError;
-use crate::data::{self, Symbol};
+use crate::data::{self, Security};
+use crate::data::parse::{ParseError, RowError, SkipReport};
+use crate::data::symbols::{NasdaqListed, OtherListed};
+use crate::data::calendar::MarketCalendar;
+use crate::util::calendar::TradingCalendar;
 
 const SYMBOLS_DIRECTORY: &str = "SymbolDirectory";
 const NASDAQ_SYMBOLS_FILENAME: &str = "nasdaqlisted.txt";
 const OTHER_SYMBOLS_FILENAME: &str = "otherlisted.txt";
 const OPTIONS_FILENAME: &str = "options.txt";
+const FOOTER_PREFIX: &str = "File Creation Time: ";
+
+/// Every security in the directory, from both of its files, and a report
+/// for each file of the rows that couldn't be read. Test issues are left out.
+pub fn load_symbols(calendar: &MarketCalendar) -> Result<(Vec<Security>, Vec<SkipReport>), CliError> {
+    let (nasdaq, nasdaq_report) = load_file::<NasdaqListed>(NASDAQ_SYMBOLS_FILENAME, calendar)?;
+    let (other, other_report) = load_file::<OtherListed>(OTHER_SYMBOLS_FILENAME, calendar)?;
+    let securities = nasdaq.into_iter().map(Security::from)
+        .chain(other.into_iter().map(Security::from))
+        .filter(|s| !s.test_issue)
+        .collect();
+    Ok((securities, vec![nasdaq_report, other_report]))
+}
 
-pub fn load_symbols() -> Result<Vec<Symbol>, CliError> {
-    type Res = Result<(Vec<Symbol>, chrono::Date<FixedOffset>), io::Error>;
-    let nasdaq_data: Res = read_nasdaq_file(NASDAQ_SYMBOLS_FILENAME);
-    let other_data: Res = read_nasdaq_file(OTHER_SYMBOLS_FILENAME);
-    let mut nasdaq_result: Vec<Symbol>;
-    let mut other_result: Vec<Symbol>;
-    
-    if nasdaq_data.is_err() {
-        create_dir_if_necessary()?;
-        nasdaq_result = refresh_file_from_remote(NASDAQ_SYMBOLS_FILENAME)?;
-    } else {
-        let (_nasdaq_result, nasdaq_date) = nasdaq_data.unwrap();
-        if is_outdated(nasdaq_date) {
-            nasdaq_result = refresh_file_from_remote(NASDAQ_SYMBOLS_FILENAME)?;
-        } else {
-            nasdaq_result = _nasdaq_result;
-        }
-    }
-
-    if other_data.is_err() {
-        create_dir_if_necessary()?;
-        other_result = refresh_file_from_remote(OTHER_SYMBOLS_FILENAME)?;
-    } else {
-        let (_other_result, other_date) = other_data.unwrap();
-        if is_outdated(other_date) {
-            other_result = refresh_file_from_remote(OTHER_SYMBOLS_FILENAME)?;
-        } else {
-            other_result = _other_result;
-        }
-    }
-
-    nasdaq_result.append(&mut other_result);
-    Ok(nasdaq_result)
+pub fn load_options(calendar: &MarketCalendar) -> Result<(Vec<data::Option>, SkipReport), CliError> {
+    load_file(OPTIONS_FILENAME, calendar)
 }
 
-pub fn load_options() -> Result<Vec<data::Option>, CliError> {
-    let data = read_nasdaq_file(OPTIONS_FILENAME);
+type Parsed<T> = (Vec<T>, SkipReport);
+
+/// Read `file` from the symbols directory, fetching it first if it's missing
+/// or out of date.
+fn load_file<T: FromStr<Err = RowError>>(file: &str, calendar: &MarketCalendar) -> Result<Parsed<T>, CliError> {
+    let data = read_nasdaq_file(file);
     if data.is_err() {
         create_dir_if_necessary()?;
-        return refresh_file_from_remote(OPTIONS_FILENAME)
+        return refresh_file_from_remote(file)
     }
-    let (result, date) = data?;
-    if is_outdated(date) {
-        refresh_file_from_remote(OPTIONS_FILENAME)  
+    let (result, report, date) = data?;
+    if is_outdated(date, calendar) {
+        refresh_file_from_remote(file)
     } else {
-        Ok(result)
+        Ok((result, report))
     }
 }
 
 // TODO: Pass in an Arc<Mutex<FtpStream>>
-fn refresh_file_from_remote<T: std::str::FromStr>(file: &str) -> Result<Vec<T>, CliError> {
+fn refresh_file_from_remote<T: FromStr<Err = RowError>>(file: &str) -> Result<Parsed<T>, CliError> {
     let mut ftp_stream = create_ftp_stream()
         .map_err(|err| CliError::InitError{ msg: err.to_string() })?;
-    fetch_and_write_nasdaq_file::<T>(&mut ftp_stream, file)?;
+    fetch_and_write_nasdaq_file(&mut ftp_stream, file)?;
     ftp_stream.quit().unwrap();
     read_nasdaq_file::<T>(file)
-        .map(|(res, _)| res)
+        .map(|(res, report, _)| (res, report))
         .map_err(|e| CliError::InitError { msg: e.to_string() })
 }
 
@@ -81,24 +73,40 @@ fn create_ftp_stream() -> Result<ftp::FtpStream, ftp::FtpError> {
     Ok(ftp_stream)
 }
 
-fn read_nasdaq_file<T: std::str::FromStr>(file: &str) -> Result<(Vec<T>, Date<FixedOffset>), io::Error> {
+fn read_nasdaq_file<T: FromStr<Err = RowError>>(file: &str) -> Result<(Vec<T>, SkipReport, Date<Tz>), io::Error> {
     let contents = fs::read_to_string(relative_filepath(file))?;
-    let lines: Vec<&str> = contents.split("\n").collect();
-    // Remove the first line (header) and last line (empty newline)
-    let mut lines = lines[1..lines.len() - 1].to_vec();
-    let last_line = lines.pop().unwrap();
-    let file_creation_date = get_file_creation_date(last_line);
-    let result: Vec<T> = lines
-        .into_iter()
-        .map(|line| line.parse::<T>())
-        .filter_map(Result::ok)
+    Ok(parse_nasdaq_file(file, &contents))
+}
+
+/// Read every row of `contents`, the text of `file`, that parses. The first
+/// line is a header and the last a footer with the date the file was made.
+fn parse_nasdaq_file<T: FromStr<Err = RowError>>(file: &str, contents: &str) -> (Vec<T>, SkipReport, Date<Tz>) {
+    let mut lines: Vec<(usize, &str)> = contents.lines()
+        .enumerate()
+        .skip(1)
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
         .collect();
-    Ok((result, file_creation_date))
+    let footer = match lines.last() {
+        Some((_, line)) if line.starts_with(FOOTER_PREFIX) => lines.pop().map(|(_, line)| line),
+        _ => None
+    };
+    let file_creation_date = footer.and_then(get_file_creation_date).unwrap_or_else(util::today);
+    let mut report = SkipReport::new(file);
+    let mut result = vec![];
+    for (line_number, line) in lines {
+        match line.parse::<T>() {
+            Ok(row) => result.push(row),
+            Err(error) => report.skipped.push(ParseError { file: file.to_string(), line: line_number, error })
+        }
+    }
+    report.loaded = result.len();
+    (result, report, file_creation_date)
 }
 
-fn fetch_and_write_nasdaq_file<T>(ftp_stream: &mut FtpStream, file: &str) -> Result<(), io::Error> {
+fn fetch_and_write_nasdaq_file(ftp_stream: &mut FtpStream, file: &str) -> Result<(), io::Error> {
     let bytes = fetch_remote_file(ftp_stream, file)
-        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+        .map_err(|err| io::Error::other(err.to_string()))?;
     write_remote_file(&bytes[..], file)
 }
 
@@ -116,39 +124,27 @@ fn fetch_remote_file(ftp_stream: &mut FtpStream, file: &str) -> Result<Vec<u8>,
 fn write_remote_file(bytes: &[u8], file: &str) -> Result<(), std::io::Error> {
     // Write to filesystem
     let mut buffer = File::create(relative_filepath(file))?;
-    buffer.write_all(&bytes)?;
+    buffer.write_all(bytes)?;
     Ok(())
 }
 
-fn get_file_creation_date(line: &str) -> Date<FixedOffset> { 
-
-    let start_index = "File Creation Time: ".len();
-    let end_index = line.find("|");
-
-    // If we can't parse the file creation date, just assume it's today's date.symbols
-    // That means the file won't be refreshed. Is this bad?
-    if let None = end_index {
-        let local_date = chrono::offset::Local::today().naive_local();
-        util::est().from_local_date(&local_date).unwrap()
-    } else {
-        let segment = line[start_index..end_index.unwrap()].to_string();
-        let month_component = &segment[..2];
-        let day_component = &segment[2..4];
-        let year_component = &segment[4..8];
-        util::est().ymd(year_component.parse().unwrap(),
-                  month_component.parse().unwrap(), 
-                  day_component.parse().unwrap())
-    }  
+/// The date in a footer like `File Creation Time: 0612202016:31|||||`, as
+/// MMDDYYYY. None if it can't be read.
+fn get_file_creation_date(line: &str) -> Option<Date<Tz>> { 
+    let segment = line.get(FOOTER_PREFIX.len()..)?;
+    let month = segment.get(..2)?.parse().ok()?;
+    let day = segment.get(2..4)?.parse().ok()?;
+    let year = segment.get(4..8)?.parse().ok()?;
+    util::eastern().ymd_opt(year, month, day).single()
 }
 
-fn is_outdated(creation_date: Date<FixedOffset>) -> bool {
-    let today = chrono::offset::Local::today();
-    let est = util::est();
-    let today = today.with_timezone(&est);
-    if today.weekday().number_from_monday() > 5 || util::is_market_holiday(today) {
+fn is_outdated(creation_date: Date<Tz>, calendar: &MarketCalendar) -> bool {
+    // NASDAQ only publishes new files on trading days.
+    let today = util::today();
+    if !calendar.is_trading_day(today.naive_local()) {
         false
     } else {
-        creation_date != chrono::offset::Local::today()
+        creation_date != today
     }
 }
 
@@ -162,4 +158,56 @@ impl From<io::Error> for CliError {
     fn from(err: io::Error) -> Self {
         CliError::InitError { msg: err.to_string() }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NASDAQ_FILE: &str = "\
+Symbol|Security Name|Market Category|Test Issue|Financial Status|Round Lot Size|ETF|NextShares
+AAPL|Apple Inc. - Common Stock|Q|N|N|100|N|N
+BAD|Bad Row|Q|N
+
+MSFT|Microsoft Corporation - Common Stock|Q|N|N|100|N|N
+XYZ|XYZ Corp|X|N|N|100|N|N
+File Creation Time: 0612202016:31|||||||
+";
+
+    #[test]
+    fn skipped_rows_keep_their_line_numbers() {
+        let (listed, report, _) = parse_nasdaq_file::<NasdaqListed>(NASDAQ_SYMBOLS_FILENAME, NASDAQ_FILE);
+        let symbols: Vec<&str> = listed.iter().map(|l| l.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["AAPL", "MSFT"]);
+        assert_eq!(report.loaded, 2);
+        let skipped: Vec<String> = report.skipped.iter().map(|e| e.to_string()).collect();
+        assert_eq!(skipped, vec![
+            "nasdaqlisted.txt:3:5: expected 8 columns, found 4",
+            "nasdaqlisted.txt:6:3: invalid Market Category \"X\""
+        ]);
+    }
+
+    #[test]
+    fn the_footer_dates_the_file() {
+        let (_, report, date) = parse_nasdaq_file::<NasdaqListed>(NASDAQ_SYMBOLS_FILENAME, NASDAQ_FILE);
+        assert_eq!(date, util::eastern().ymd(2020, 6, 12));
+        // The footer isn't a row, so it isn't skipped as one.
+        assert!(report.skipped.iter().all(|e| e.line != 7));
+    }
+
+    #[test]
+    fn a_file_without_a_footer_is_from_today() {
+        let contents = NASDAQ_FILE.lines().take(2).collect::<Vec<_>>().join("\n");
+        let (listed, report, date) = parse_nasdaq_file::<NasdaqListed>(NASDAQ_SYMBOLS_FILENAME, &contents);
+        assert_eq!(listed.len(), 1);
+        assert!(report.skipped.is_empty());
+        assert_eq!(date, util::today());
+    }
+
+    #[test]
+    fn footers_are_read_as_month_day_year() {
+        assert_eq!(get_file_creation_date("File Creation Time: 1231201917:02|||||"), Some(util::eastern().ymd(2019, 12, 31)));
+        assert_eq!(get_file_creation_date("File Creation Time: 1331201917:02|||||"), None);
+        assert_eq!(get_file_creation_date("File Creation Time: 12"), None);
+    }
+}
@@ -1,21 +1,44 @@
 mod loader;
+mod account;
 mod cache;
+mod chain;
+mod search;
+mod ticket;
+mod watchlist;
 pub mod ui;
 pub mod event;
 
 use std::fmt;
 use std::error::Error;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
+use futures::{FutureExt, StreamExt};
 use tokio::sync::Mutex;
-use chrono::{Datelike, Date, Timelike, DateTime, TimeZone};
+use chrono::{Datelike, NaiveDate};
 pub use termion::event::Key;
 
-use crate::data::{self, Symbol, Quote};
-use crate::api::client;
+use crate::data::{self, Security, Quote};
+use crate::data::parse::SkipReport;
+use crate::data::symbology;
+use crate::data::watchlist::{Lookups, WatchlistEntry, WatchlistsFile};
+use crate::data::calendar::{DayStatus, MarketCalendar};
+use crate::data::clock::MarketClock;
+use crate::api::{AccountProvider, MarketDataProvider, HistoryInterval, Interval, SessionFilter};
+use crate::api::error::{ApiError, ErrorKind};
+use crate::api::retry::RetryPolicy;
+use crate::api::stream::{EventStream, Reconnect};
+use crate::api::order::{OrderRequest, OrderSide};
+use crate::api::strategy::{Strategy, StrategyKind};
 use crate::util;
-use cache::{QuoteCache, GraphCache};
-use ui::{StatefulList, ViewContext, Listable};
+use cache::{QuoteCache, GraphCache, HistoryCache, ChainCache};
+use chain::{ChainState, StrategyBuilder};
+use account::AccountState;
+use ticket::{OrderTicket, TicketStage};
+use search::SearchState;
+use watchlist::WatchlistSync;
+use ui::{ChartRange, StatefulList, ViewContext, Listable};
 
 #[derive(Debug)]
 pub enum CliError {
@@ -32,103 +55,629 @@ impl fmt::Display for CliError {
     }
 }
 
+/// Rejected directory rows logged by location; the rest are only counted.
+const MAX_LOGGED_SKIPS: usize = 3;
+
 pub struct App { 
     pub title: String,
-    pub symbols: Vec<Symbol>,
+    pub symbols: Vec<Security>,
     pub options: Vec<data::Option>,
-    pub watchlist: StatefulList<Symbol>,
+    pub watchlist: StatefulList<WatchlistEntry>,
+    /// Lookups of watchlist tickers missing from the directory.
+    pub lookups: Lookups,
+    pub watchlist_sync: WatchlistSync,
+    /// Every saved watchlist. The showing one is kept in `watchlist_sync`
+    /// and copied back here when it changes.
+    pub watchlists: WatchlistsFile,
+    /// Where `watchlists` is saved. None if there's nowhere to save it, or
+    /// the file couldn't be read and shouldn't be written over.
+    pub watchlists_path: Option<PathBuf>,
     pub log: Vec<String>,
     pub should_quit: bool,
+    pub session_filter: SessionFilter,
+    pub interval: Interval,
+    pub chart_range: ChartRange,
+    pub calendar: MarketCalendar,
+    pub clock: Option<MarketClock>,
+    pub search: SearchState,
+    pub chain: ChainState,
+    pub account: AccountState,
+    pub ticket: Option<OrderTicket>,
+    /// Whether quotes are being kept current by the event stream.
+    pub streaming: bool,
+    provider: Arc<dyn MarketDataProvider>,
+    accounts: Arc<dyn AccountProvider>,
     quote_cache: QuoteCache,
     graph_cache: GraphCache,
+    history_cache: HistoryCache,
+    chain_cache: ChainCache,
     active_context: ViewContext,
+    clock_pending: bool,
 }
 
 impl App { 
-    pub fn new() -> Self { 
+    pub fn new(provider: Arc<dyn MarketDataProvider>, accounts: Arc<dyn AccountProvider>) -> Self { 
         App {
-            title: format!("StonksCLI"),
+            title: "StonksCLI".to_string(),
             options: vec![],
             symbols: vec![],
             active_context: ViewContext::Watchlist,
             watchlist: StatefulList::default(),
+            lookups: Lookups::new(),
+            watchlist_sync: WatchlistSync::from_saved(&WatchlistsFile::default().active()),
+            watchlists: WatchlistsFile::default(),
+            watchlists_path: None,
             quote_cache: QuoteCache::new(),
             graph_cache: GraphCache::new(),
+            history_cache: HistoryCache::new(),
             log: vec![],
             should_quit: false,
+            session_filter: SessionFilter::Open,
+            interval: Interval::FiveMinutes,
+            chart_range: ChartRange::Intraday,
+            calendar: MarketCalendar::new(),
+            clock: None,
+            search: SearchState::default(),
+            chain: ChainState::default(),
+            account: AccountState::default(),
+            ticket: None,
+            streaming: false,
+            chain_cache: ChainCache::new(),
+            clock_pending: false,
+            provider,
+            accounts,
+        }
+    }
+
+    pub fn on_key(&mut self, c: char, app: Arc<Mutex<App>>) {
+        if self.ticket.is_some() {
+            return self.on_ticket_key(c, app);
+        }
+        if self.search.editing {
+            match c {
+                '\n' => self.submit_search(app),
+                c => self.search.query.push(c)
+            }
+            return;
+        }
+        let in_options = matches!(self.active_context, ViewContext::Options);
+        match c {
+            's' if in_options => self.cycle_strategy(),
+            'b' if in_options => self.toggle_strategy_side(),
+            '+' if in_options => self.change_strategy_width(1),
+            '-' if in_options => self.change_strategy_width(-1),
+            'b' if matches!(self.active_context, ViewContext::Account) => self.open_bracket(),
+            'w' => self.add_to_watchlist(app),
+            'd' if matches!(self.active_context, ViewContext::Watchlist) => self.remove_from_watchlist(app),
+            '[' => self.switch_watchlist(false, app),
+            ']' => self.switch_watchlist(true, app),
+            'q' => self.should_quit = true,
+            '/' => self.start_search(),
+            'o' => self.open_options(app),
+            'a' => self.open_account(app),
+            't' => self.open_ticket(),
+            'e' => self.toggle_extended_hours(app),
+            'i' => self.cycle_interval(app),
+            '1' => self.set_chart_range(ChartRange::Intraday, app),
+            '2' => self.set_chart_range(ChartRange::OneMonth, app),
+            '3' => self.set_chart_range(ChartRange::SixMonths, app),
+            '4' => self.set_chart_range(ChartRange::OneYear, app),
+            '5' => self.set_chart_range(ChartRange::FiveYears, app),
+            _ => {}
+        }
+    }
+
+    /// Switch the graph between the regular session and the whole day,
+    /// including pre-market and after-hours trading.
+    fn toggle_extended_hours(&mut self, app: Arc<Mutex<App>>) {
+        self.session_filter = match self.session_filter {
+            SessionFilter::Open => SessionFilter::All,
+            SessionFilter::All => SessionFilter::Open
+        };
+        self.refresh_graph(app);
+    }
+
+    fn cycle_interval(&mut self, app: Arc<Mutex<App>>) {
+        self.interval = match self.interval {
+            Interval::Tick => Interval::OneMinute,
+            Interval::OneMinute => Interval::FiveMinutes,
+            Interval::FiveMinutes => Interval::FifteenMinutes,
+            Interval::FifteenMinutes => Interval::Tick
+        };
+        self.refresh_graph(app);
+    }
+
+    /// Whether keys are going to the search prompt.
+    pub fn is_typing(&self) -> bool {
+        self.search.editing || self.ticket.is_some()
+    }
+
+    fn start_search(&mut self) {
+        self.search.editing = true;
+        self.search.query.clear();
+    }
+
+    fn submit_search(&mut self, app: Arc<Mutex<App>>) {
+        self.search.editing = false;
+        let query = self.search.query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        tokio::spawn(async move {
+            background_search(app, query).await;
+        });
+    }
+
+    /// Show the options chain for the selected symbol.
+    fn open_options(&mut self, app: Arc<Mutex<App>>) {
+        if let ViewContext::Options = self.active_context {
+            return;
+        }
+        let symbol = self.selected_ticker().to_string();
+        self.chain = ChainState::new(symbol.clone());
+        self.active_context = ViewContext::Options;
+        tokio::spawn(async move {
+            background_fetch_expirations(app, symbol).await;
+        });
+    }
+
+    /// Show the brokerage account, reloading it each time it's opened.
+    fn open_account(&mut self, app: Arc<Mutex<App>>) {
+        self.active_context = ViewContext::Account;
+        if self.account.loading {
+            return;
+        }
+        self.account.loading = true;
+        tokio::spawn(async move {
+            background_fetch_account(app).await;
+        });
+    }
+
+    /// Add the selected symbol to the watchlist, here and on Tradier.
+    fn add_to_watchlist(&mut self, app: Arc<Mutex<App>>) {
+        let ticker = self.selected_ticker().to_string();
+        if ticker.is_empty() || !self.watchlist_sync.add(&ticker) {
+            return;
+        }
+        self.log.push(format!("Added ${} to the watchlist.", ticker));
+        self.rebuild_watchlist();
+        self.save_watchlists();
+        let verify_app = Arc::clone(&app);
+        tokio::spawn(async move {
+            background_verify_watchlist(verify_app).await;
+        });
+        self.sync_watchlist(app);
+    }
+
+    fn remove_from_watchlist(&mut self, app: Arc<Mutex<App>>) {
+        let ticker = self.selected_ticker().to_string();
+        if ticker.is_empty() || !self.watchlist_sync.remove(&ticker) {
+            return;
+        }
+        self.log.push(format!("Removed ${} from the watchlist.", ticker));
+        self.rebuild_watchlist();
+        self.save_watchlists();
+        self.sync_watchlist(app);
+    }
+
+    /// Show the next or previous saved watchlist, and sync it with Tradier if
+    /// it syncs.
+    fn switch_watchlist(&mut self, forward: bool, app: Arc<Mutex<App>>) {
+        let name = match self.watchlists.neighbour(&self.watchlist_sync.name, forward) {
+            Some(name) if name != self.watchlist_sync.name => name.to_string(),
+            _ => {
+                let path = self.watchlists_path.as_ref().map_or("the watchlists file".to_string(), |p| p.display().to_string());
+                return self.log.push(format!("No other watchlists. Add some to {}.", path));
+            }
+        };
+        let saved = match self.watchlists.find(&name) {
+            Some(saved) => saved.clone(),
+            None => return
+        };
+        self.watchlist_sync = WatchlistSync::from_saved(&saved);
+        self.watchlist.state.select(None);
+        self.rebuild_watchlist();
+        self.save_watchlists();
+        if saved.syncs() {
+            self.log.push(format!("Showing watchlist \"{}\".", name));
+        } else {
+            self.log.push(format!("Showing watchlist \"{}\", which only this app keeps.", name));
+        }
+
+        let selected = self.selected_ticker().to_string();
+        let fetch_app = Arc::clone(&app);
+        tokio::spawn(async move {
+            background_fetch_watchlist_quotes(Arc::clone(&fetch_app)).await;
+            background_verify_watchlist(Arc::clone(&fetch_app)).await;
+            background_fetch_graph(fetch_app, selected).await;
+        });
+        self.sync_watchlist(app);
+    }
+
+    /// Copy the showing watchlist back into `watchlists` and write them all
+    /// to their file.
+    fn save_watchlists(&mut self) {
+        let sync = &self.watchlist_sync;
+        // Until a sync, `base` is only a guess at what Tradier has.
+        let synced = if sync.id.is_some() { Some(sync.base.clone()) } else { None };
+        if let Some(saved) = self.watchlists.find_mut(&sync.name) {
+            saved.symbols = sync.tickers.clone();
+            if synced.is_some() {
+                saved.synced = synced;
+            }
         }
+        self.watchlists.active = sync.name.clone();
+        self.write_watchlists();
+    }
+
+    fn write_watchlists(&mut self) {
+        let path = match &self.watchlists_path {
+            Some(path) => path,
+            None => return
+        };
+        if let Err(err) = watchlist::save_watchlists(path, &self.watchlists) {
+            let msg = format!("Failed to save watchlists to {}: {}", path.display(), err);
+            self.log.push(msg);
+        }
+    }
+
+    /// Read the saved watchlists from `path`, or the default location, and
+    /// show the one that was showing last.
+    fn load_watchlists(&mut self, path: Option<PathBuf>) {
+        self.watchlists_path = path.or_else(watchlist::default_store_path);
+        if let Some(path) = self.watchlists_path.clone() {
+            match watchlist::load_watchlists(&path) {
+                Ok(watchlists) => self.watchlists = watchlists,
+                Err(err) => {
+                    let msg = format!("Failed to read watchlists from {}, so they won't be saved: {}", path.display(), err);
+                    self.log.push(msg);
+                    self.watchlists_path = None;
+                }
+            }
+        }
+        self.watchlist_sync = WatchlistSync::from_saved(&self.watchlists.active());
+    }
+
+    /// Show the synced tickers, keeping the selection where it can.
+    fn rebuild_watchlist(&mut self) {
+        let selected = self.watchlist.state.selected().and_then(|i| self.watchlist.list.get(i)).map(|s| s.symbol.clone());
+        let list = data::watchlist::get_watch_list(&self.symbols, &self.watchlist_sync.tickers, &self.lookups);
+        let index = selected.and_then(|selected| list.iter().position(|s| s.symbol == selected))
+            .unwrap_or(0);
+        let empty = list.is_empty();
+        self.watchlist = StatefulList::with_list(list);
+        self.watchlist.state.select(if empty { None } else { Some(index) });
+    }
+
+    fn sync_watchlist(&mut self, app: Arc<Mutex<App>>) {
+        if !self.watchlist_sync.syncs {
+            return;
+        }
+        if self.watchlist_sync.syncing {
+            self.watchlist_sync.pending = true;
+            return;
+        }
+        self.watchlist_sync.syncing = true;
+        tokio::spawn(async move {
+            background_sync_watchlist(app).await;
+        });
+    }
+
+    /// Open the options builder, or move it on to the next strategy.
+    fn cycle_strategy(&mut self) {
+        match self.chain.builder.as_mut() {
+            Some(builder) => {
+                let index = StrategyKind::ALL.iter().position(|k| *k == builder.kind).unwrap_or(0);
+                builder.kind = StrategyKind::ALL[(index + 1) % StrategyKind::ALL.len()];
+            },
+            None => self.chain.builder = Some(StrategyBuilder::new())
+        }
+    }
+
+    fn toggle_strategy_side(&mut self) {
+        if let Some(builder) = self.chain.builder.as_mut() {
+            builder.buy = !builder.buy;
+        }
+    }
+
+    fn change_strategy_width(&mut self, change: isize) {
+        if let Some(builder) = self.chain.builder.as_mut() {
+            builder.width = (builder.width as isize + change).max(1) as usize;
+        }
+    }
+
+    /// Move the spread up or down the chain.
+    fn shift_strategy(&mut self, offset: isize) {
+        let expiration = match self.chain.selected_expiration() {
+            Some(expiration) => expiration,
+            None => return
+        };
+        let chain = match self.chain_cache.get(&(self.chain.symbol.clone(), expiration)) {
+            Some(chain) => chain,
+            None => return
+        };
+        let last = self.quote_cache.get(&self.chain.symbol).and_then(|q| q.last).map(|l| l as f64);
+        if let Some(builder) = self.chain.builder.as_mut() {
+            builder.shift(chain, last, offset);
+        }
+    }
+
+    /// The spread in the options builder, priced from the loaded chain.
+    pub fn strategy(&self) -> Option<Strategy> {
+        let chain = self.get_chain(&self.chain.symbol, self.chain.selected_expiration()?)?;
+        let last = self.get_quote(&self.chain.symbol).and_then(|q| q.last).map(|l| l as f64);
+        self.chain.strategy(chain, last)
     }
 
-    pub fn on_key(&mut self, c: char) {
-        if c == 'q' { 
-            self.should_quit = true;
+    /// Put take-profit and stop-loss exits on the selected position.
+    fn open_bracket(&mut self) {
+        if let Some(position) = self.account.selected_position() {
+            self.ticket = Some(OrderTicket::bracket(position));
         }
     }
 
+    /// Open an order ticket for the selected symbol. From the account view
+    /// it starts as closing the selected position, and from the options
+    /// builder it's for the spread being built.
+    fn open_ticket(&mut self) {
+        let symbol = self.selected_ticker().to_string();
+        if symbol.is_empty() {
+            return;
+        }
+        if matches!(self.active_context, ViewContext::Options) {
+            if let Some(strategy) = self.strategy() {
+                self.ticket = Some(OrderTicket::multileg(strategy));
+                return;
+            }
+        }
+        let ticket = match (&self.active_context, self.account.selected_position()) {
+            (ViewContext::Account, Some(position)) => OrderTicket::close(position),
+            _ => OrderTicket::new(symbol, OrderSide::Buy, 1)
+        };
+        self.ticket = Some(ticket);
+    }
+
+    /// Tab moves between fields, Enter previews, and only `y` on a preview
+    /// places the order.
+    fn on_ticket_key(&mut self, c: char, app: Arc<Mutex<App>>) {
+        let ticket = self.ticket.as_mut().unwrap();
+        match (c, &ticket.stage) {
+            ('\t', _) => ticket.move_focus(true),
+            ('\n', TicketStage::Submitted(_)) => self.ticket = None,
+            ('\n', _) if ticket.is_editable() => match ticket.request() {
+                Ok(request) => {
+                    ticket.stage = TicketStage::Previewing;
+                    tokio::spawn(async move {
+                        background_preview_order(app, request).await;
+                    });
+                },
+                Err(msg) => ticket.stage = TicketStage::Failed(msg)
+            },
+            ('y', TicketStage::Previewed(_)) => {
+                if let Ok(request) = ticket.request() {
+                    ticket.stage = TicketStage::Submitting;
+                    tokio::spawn(async move {
+                        background_place_order(app, request).await;
+                    });
+                }
+            },
+            ('n', TicketStage::Previewed(_)) => ticket.stage = TicketStage::Editing,
+            (c, _) => ticket.input(c)
+        }
+    }
+
+    pub fn on_left(&mut self) {
+        if let Some(ticket) = self.ticket.as_mut() {
+            ticket.cycle(false);
+        } else {
+            self.shift_strategy(-1);
+        }
+    }
+
+    pub fn on_right(&mut self) {
+        if let Some(ticket) = self.ticket.as_mut() {
+            ticket.cycle(true);
+        } else {
+            self.shift_strategy(1);
+        }
+    }
+
+    pub fn on_backspace(&mut self) {
+        if let Some(ticket) = self.ticket.as_mut() {
+            ticket.backspace();
+        } else if self.search.editing {
+            self.search.query.pop();
+        }
+    }
+
+    /// Cancel the search prompt, or leave the search results for the
+    /// watchlist.
+    pub fn on_escape(&mut self, app: Arc<Mutex<App>>) {
+        if let Some(ticket) = &self.ticket {
+            // Once submitted, the order's fate is out of our hands; keep the
+            // ticket up until Tradier answers.
+            if let TicketStage::Submitting = ticket.stage {
+                return;
+            }
+            self.ticket = None;
+        } else if self.search.editing {
+            self.search.editing = false;
+        } else if self.chain.builder.is_some() {
+            self.chain.builder = None;
+        } else if let ViewContext::Search | ViewContext::Options | ViewContext::Account = self.active_context {
+            self.active_context = ViewContext::Watchlist;
+            self.fetch_graph(app);
+        }
+    }
+
+    fn refresh_graph(&mut self, app: Arc<Mutex<App>>) {
+        self.graph_cache.clear();
+        self.fetch_graph(app);
+    }
+
+    fn set_chart_range(&mut self, range: ChartRange, app: Arc<Mutex<App>>) {
+        self.chart_range = range;
+        self.fetch_graph(app);
+    }
+
+    fn fetch_graph(&self, app: Arc<Mutex<App>>) {
+        let symbol = self.selected_ticker().to_string();
+        tokio::spawn(async move {
+            background_fetch_graph(app, symbol).await;
+        });
+    }
+
     pub fn on_up(&mut self, app: Arc<Mutex<App>>) {
+        if let Some(ticket) = self.ticket.as_mut() {
+            return ticket.move_focus(false);
+        }
         match self.active_context {
-            ViewContext::Watchlist => {
-                self.watchlist.previous();
-                let symbol = self.selected_ticker().to_string();
-                tokio::spawn(async move {
-                    background_fetch_graph(app, symbol).await;
-                });
-            }
+            ViewContext::Watchlist if self.watchlist.list.is_empty() => return,
+            ViewContext::Watchlist => self.watchlist.previous(),
+            ViewContext::Search => self.search.results.previous(),
+            ViewContext::Options => return self.move_expiration(app, false),
+            ViewContext::Account => return self.move_position(false)
         }
+        self.fetch_graph(app);
     }
 
     pub fn on_down(&mut self, app: Arc<Mutex<App>>) {
+        if let Some(ticket) = self.ticket.as_mut() {
+            return ticket.move_focus(true);
+        }
         match self.active_context {
-            ViewContext::Watchlist => {
-                self.watchlist.next();
-                let symbol = self.selected_ticker().to_string();
-                tokio::spawn(async move {
-                    background_fetch_graph(app, symbol).await;
-                });
-            }
+            ViewContext::Watchlist if self.watchlist.list.is_empty() => return,
+            ViewContext::Watchlist => self.watchlist.next(),
+            ViewContext::Search => self.search.results.next(),
+            ViewContext::Options => return self.move_expiration(app, true),
+            ViewContext::Account => return self.move_position(true)
         }
+        self.fetch_graph(app);
     }
 
-    pub fn on_tick(&mut self) {
+    fn move_position(&mut self, forward: bool) {
+        let positions = &mut self.account.positions;
+        if positions.list.is_empty() {
+            return;
+        }
+        if forward { positions.next() } else { positions.previous() }
+    }
 
+    fn move_expiration(&mut self, app: Arc<Mutex<App>>, forward: bool) {
+        let expirations = &mut self.chain.expirations;
+        if expirations.list.is_empty() {
+            return;
+        }
+        if forward { expirations.next() } else { expirations.previous() }
+        let symbol = self.chain.symbol.clone();
+        let expiration = self.chain.selected_expiration().unwrap();
+        tokio::spawn(async move {
+            background_fetch_chain(app, symbol, expiration).await;
+        });
+    }
+
+    /// Once the market clock's next change has passed, fill in the new
+    /// session from the calendar and ask the provider for the real clock.
+    pub fn on_tick(&mut self, app: Arc<Mutex<App>>) {
+        let now = util::now();
+        if self.clock.as_ref().is_some_and(|c| !c.is_stale(now)) {
+            return;
+        }
+        self.clock = Some(MarketClock::from_calendar(&self.calendar, now));
+        if !self.clock_pending {
+            self.clock_pending = true;
+            tokio::spawn(async move {
+                background_fetch_clock(app).await;
+            });
+        }
+    }
+
+    /// Log how many rows of a directory file were rejected and why, with
+    /// where the first few are.
+    fn log_skip_report(&mut self, report: &SkipReport) {
+        if let Some(summary) = report.summary() {
+            self.log.push(summary);
+        }
+        for error in report.skipped.iter().take(MAX_LOGGED_SKIPS) {
+            self.log.push(format!("  {}", error));
+        }
+    }
+
+    /// Every symbol with a quote, which is everything on screen worth
+    /// streaming.
+    fn streamable_symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.quote_cache.keys().cloned().collect();
+        symbols.sort();
+        symbols
     }
 
     pub fn get_quote(&self, ticker: &str) -> Option<&Quote> { 
         self.quote_cache.get(ticker)
     }
 
+    pub fn get_history(&self, ticker: &str) -> Option<&data::History> {
+        self.history_cache.get(&(ticker.to_string(), self.chart_range))
+    }
+
+    pub fn get_chain(&self, ticker: &str, expiration: NaiveDate) -> Option<&data::OptionChain> {
+        self.chain_cache.get(&(ticker.to_string(), expiration))
+    }
+
     pub fn selected_ticker(&self) -> &str {
         match self.active_context {
             ViewContext::Watchlist => {
-                let index = self.watchlist.state.selected().unwrap();
-                return &self.watchlist.list[index].symbol;
-            }
+                let index = self.watchlist.state.selected();
+                index.and_then(|i| self.watchlist.list.get(i)).map_or("", |s| &s.symbol)
+            },
+            ViewContext::Search => {
+                let index = self.search.results.state.selected().unwrap();
+                &self.search.results.list[index].symbol
+            },
+            ViewContext::Options => &self.chain.symbol,
+            ViewContext::Account => self.account.selected_position().map_or("", |p| &p.symbol)
         }
     }
 
 }
 
 pub async fn initialize(app: Arc<Mutex<App>>) -> Result<ui::Terminal, CliError> {
-    let symbols = loader::load_symbols()?;
+    load_calendar(Arc::clone(&app)).await;
+    let calendar = app.lock().await.calendar.clone();
+    let (symbols, reports) = loader::load_symbols(&calendar)?;
     let selected_symbol: String;
     {
         let mut app = app.lock().await;
         app.symbols = symbols;
+        for report in &reports {
+            app.log_skip_report(report);
+        }
+        let path = app.watchlists_path.take();
+        app.load_watchlists(path);
 
-        let watchlist = data::watchlist::get_watch_list(&app.symbols[..]);
-        app.watchlist = StatefulList::with_list(watchlist);
-        app.watchlist.state.select(Some(0));
+        app.rebuild_watchlist();
         
         selected_symbol = app.selected_ticker().to_string();
-        let msg = format!("Loaded {} symbols and watchlist.", app.symbols.len());
+        let msg = format!("Loaded {} symbols and watchlist \"{}\".", app.symbols.len(), app.watchlist_sync.name);
+        app.log.push(msg);
+        let msg = format!("Using {} market data.", app.provider.name());
         app.log.push(msg);
     }
 
     // Background tasks
+    {
+        let mut lock = app.lock().await;
+        lock.watchlist_sync.syncing = lock.watchlist_sync.syncs;
+    }
+    let stream_app = Arc::clone(&app);
     tokio::spawn(async move {
         background_fetch_options(Arc::clone(&app)).await;
         background_fetch_watchlist_quotes(Arc::clone(&app)).await;
+        background_verify_watchlist(Arc::clone(&app)).await;
         background_fetch_graph(Arc::clone(&app), selected_symbol).await;
+        background_sync_watchlist(Arc::clone(&app)).await;
+    });
+    tokio::spawn(async move {
+        background_stream_quotes(stream_app).await;
     });
 
     let mut terminal = ui::initialize_terminal()
@@ -138,53 +687,679 @@ pub async fn initialize(app: Arc<Mutex<App>>) -> Result<ui::Terminal, CliError>
     Ok(terminal)
 }
 
+/// Load this month's and last month's exchange calendars, which cover the
+/// last trading day even early in a month. On failure the calendar falls back
+/// to treating every weekday as a regular session.
+async fn load_calendar(app: Arc<Mutex<App>>) {
+    let provider = Arc::clone(&app.lock().await.provider);
+    let today = util::today().naive_local();
+    let last_month = today.with_day(1).unwrap().pred();
+    for date in [today, last_month].iter() {
+        let (year, month) = (date.year(), date.month());
+        if app.lock().await.calendar.has_month(year, month) {
+            continue;
+        }
+        let result = provider.get_calendar(year, month).await;
+        let mut lock = app.lock().await;
+        match result {
+            Ok(calendar) => lock.calendar.insert(calendar),
+            Err(err) => {
+                let context = format!("Failed to get market calendar for {}-{:02}", year, month);
+                lock.log.push(describe_error(&context, &err));
+            }
+        }
+    }
+    let mut lock = app.lock().await;
+    if let Some(day) = lock.calendar.day(today).filter(|d| d.status == DayStatus::Closed) {
+        let msg = day.description.clone();
+        lock.log.push(msg);
+    }
+}
+
+async fn background_fetch_clock(app: Arc<Mutex<App>>) {
+    let provider = Arc::clone(&app.lock().await.provider);
+    let result = provider.get_clock().await;
+    let mut lock = app.lock().await;
+    lock.clock_pending = false;
+    match result {
+        Ok(clock) => {
+            // A clock that's already out of date would just be asked for again.
+            let clock = clock.into_clock(&lock.calendar).filter(|c| !c.is_stale(util::now()));
+            if clock.is_some() {
+                lock.clock = clock;
+            }
+        },
+        Err(err) => {
+            lock.log.push(describe_error("Failed to get market clock", &err));
+        }
+    }
+}
+
+/// Sync the showing watchlist with Tradier until there are no edits left to
+/// push. Edits made while a sync is in flight are merged over its result,
+/// and a watchlist switched away from mid-sync keeps the result saved.
+async fn background_sync_watchlist(app: Arc<Mutex<App>>) {
+    let showing = {
+        let lock = app.lock().await;
+        if !lock.watchlist_sync.syncs {
+            return;
+        }
+        lock.watchlist_sync.name.clone()
+    };
+    let backoff = RetryPolicy {
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(30),
+        ..RetryPolicy::default()
+    };
+    let mut failures = 0;
+    loop {
+        let (accounts, name, id, base, local) = {
+            let mut lock = app.lock().await;
+            if lock.watchlist_sync.name != showing {
+                return;
+            }
+            lock.watchlist_sync.pending = false;
+            let sync = &lock.watchlist_sync;
+            (Arc::clone(&lock.accounts), sync.name.clone(), sync.id.clone(), sync.base.clone(), sync.tickers.clone())
+        };
+        let result = watchlist::sync(accounts.as_ref(), name.clone(), id, &base, &local).await;
+        let mut lock = app.lock().await;
+        let switched = lock.watchlist_sync.name != name;
+        let (id, merge) = match result {
+            Ok(synced) => synced,
+            Err(err) if switched => {
+                let context = format!("Failed to sync watchlist \"{}\"", name);
+                return lock.log.push(describe_error(&context, &err));
+            },
+            Err(err) => {
+                // The edits since the last sync are still only here.
+                lock.watchlist_sync.pending = true;
+                if err.is_transient() && failures < backoff.max_retries {
+                    drop(lock);
+                    tokio::time::delay_for(backoff.delay(failures)).await;
+                    failures += 1;
+                    continue;
+                }
+                lock.watchlist_sync.syncing = false;
+                let context = format!("Failed to sync watchlist \"{}\"", name);
+                return lock.log.push(describe_error(&context, &err));
+            }
+        };
+        failures = 0;
+        if switched {
+            if let Some(saved) = lock.watchlists.find_mut(&name) {
+                if saved.symbols == local {
+                    saved.symbols = merge.symbols.clone();
+                } else {
+                    saved.symbols = data::watchlist::merge(&local, &saved.symbols, &merge.symbols).symbols;
+                }
+                saved.synced = Some(merge.symbols);
+            }
+            return lock.write_watchlists();
+        }
+        if !merge.added_remotely.is_empty() {
+            let msg = format!("Added on Tradier: {}", merge.added_remotely.join(", "));
+            lock.log.push(msg);
+        }
+        if !merge.removed_remotely.is_empty() {
+            let msg = format!("Removed on Tradier: {}", merge.removed_remotely.join(", "));
+            lock.log.push(msg);
+        }
+        let sync = &mut lock.watchlist_sync;
+        sync.tickers = if sync.tickers == local {
+            merge.symbols.clone()
+        } else {
+            data::watchlist::merge(&local, &sync.tickers, &merge.symbols).symbols
+        };
+        sync.id = Some(id);
+        sync.base = merge.symbols;
+        let again = sync.pending || sync.tickers != sync.base;
+        sync.syncing = again;
+        let tickers = sync.tickers.clone();
+        let selected = lock.selected_ticker().to_string();
+        lock.rebuild_watchlist();
+        lock.save_watchlists();
+        if lock.selected_ticker() != selected {
+            let selected = lock.selected_ticker().to_string();
+            let app = Arc::clone(&app);
+            tokio::spawn(async move {
+                background_fetch_graph(app, selected).await;
+            });
+        }
+        if !again {
+            lock.log.push(format!("Watchlist \"{}\" is in sync with Tradier.", name));
+            let unquoted: Vec<String> = tickers.into_iter().filter(|t| !lock.quote_cache.contains_key(t)).collect();
+            drop(lock);
+            if !unquoted.is_empty() {
+                background_fetch_quotes(Arc::clone(&app), unquoted).await;
+            }
+            background_verify_watchlist(app).await;
+            return;
+        }
+    }
+}
+
+/// Look up the watchlist tickers the directory doesn't have. Ones the lookup
+/// can't be reached for stay unverified until the next try.
+async fn background_verify_watchlist(app: Arc<Mutex<App>>) {
+    let (provider, unverified) = {
+        let lock = app.lock().await;
+        let unverified: Vec<String> = lock.watchlist.list.iter()
+            .filter(|e| e.is_unverified())
+            .map(|e| e.symbol.clone())
+            .collect();
+        (Arc::clone(&lock.provider), unverified)
+    };
+    if unverified.is_empty() {
+        return;
+    }
+    let mut lookups = vec![];
+    let mut failure = None;
+    for symbol in unverified {
+        match provider.lookup(symbol.clone()).await {
+            Ok(securities) => {
+                let found = securities.matches().iter().find(|m| symbology::same_symbol(&m.symbol, &symbol)).cloned();
+                lookups.push((symbol, found));
+            },
+            // Offline, every other lookup would fail the same way.
+            Err(err) => {
+                failure = Some(err);
+                break;
+            }
+        }
+    }
+    let mut lock = app.lock().await;
+    if let Some(err) = failure {
+        lock.log.push(describe_error("Couldn't verify watchlist symbols, so they're marked unverified", &err));
+    }
+    for (symbol, found) in lookups {
+        if found.is_none() {
+            lock.log.push(format!("${} wasn't found by symbol lookup.", symbol));
+        }
+        lock.lookups.insert(symbol, found);
+    }
+    lock.rebuild_watchlist();
+}
+
 async fn background_fetch_options(app: Arc<Mutex<App>>) {
-    if let Ok(options) = loader::load_options() {
+    let calendar = app.lock().await.calendar.clone();
+    if let Ok((options, report)) = loader::load_options(&calendar) {
         let mut app = app.lock().await;
         app.options = options;
+        app.log_skip_report(&report);
 
         let msg = format!("Loaded {} options.", app.options.len());
         app.log.push(msg);
     }
 }
 
+/// Look `query` up in the symbol directory and through the provider's
+/// symbol lookup and company search, and show what turns up.
+async fn background_search(app: Arc<Mutex<App>>, query: String) {
+    let provider;
+    let local;
+    {
+        let lock = app.lock().await;
+        provider = Arc::clone(&lock.provider);
+        local = search::local_matches(&lock.symbols, &query);
+    }
+    let (lookup, by_name) = futures::join!(provider.lookup(query.clone()), provider.search(query.clone()));
+    let mut sources = vec![local];
+    let mut lock = app.lock().await;
+    for (what, result) in [("lookup", lookup), ("search", by_name)].iter() {
+        match result {
+            Ok(securities) => sources.push(securities.matches().to_vec()),
+            Err(err) => {
+                let context = format!("Symbol {} for \"{}\" failed", what, query);
+                lock.log.push(describe_error(&context, err));
+            }
+        }
+    }
+    let results = search::merge(&query, sources);
+    if results.is_empty() {
+        lock.log.push(format!("No symbols match \"{}\".", query));
+        return;
+    }
+    lock.log.push(format!("Found {} symbols matching \"{}\".", results.len(), query));
+    let tickers: Vec<String> = results.iter().map(|m| m.symbol.clone()).collect();
+    let selected = tickers[0].clone();
+    lock.search.results = StatefulList::with_list(results);
+    lock.search.results.state.select(Some(0));
+    lock.active_context = ViewContext::Search;
+    drop(lock);
+
+    background_fetch_quotes(Arc::clone(&app), tickers).await;
+    background_fetch_graph(app, selected).await;
+}
+
+async fn background_fetch_expirations(app: Arc<Mutex<App>>, symbol: String) {
+    let provider = Arc::clone(&app.lock().await.provider);
+    let result = provider.get_option_expirations(symbol.clone()).await;
+    let mut lock = app.lock().await;
+    let expirations = match result {
+        Ok(expirations) => expirations.dates().to_vec(),
+        Err(err) => {
+            let context = format!("Failed to get option expirations for ${}", &symbol);
+            return lock.log.push(describe_error(&context, &err));
+        }
+    };
+    // The view may have moved on to another symbol in the meantime.
+    if lock.chain.symbol != symbol {
+        return;
+    }
+    let first = match expirations.first() {
+        Some(first) => *first,
+        None => return lock.log.push(format!("${} has no listed options.", &symbol))
+    };
+    lock.chain.expirations = StatefulList::with_list(expirations);
+    lock.chain.expirations.state.select(Some(0));
+    drop(lock);
+    background_fetch_chain(app, symbol, first).await;
+}
+
+async fn background_fetch_chain(app: Arc<Mutex<App>>, symbol: String, expiration: NaiveDate) {
+    let provider;
+    {
+        let lock = app.lock().await;
+        provider = Arc::clone(&lock.provider);
+        if lock.get_chain(&symbol, expiration).is_some() {
+            return;
+        }
+    }
+    let result = provider.get_option_chain(symbol.clone(), expiration).await;
+    let mut lock = app.lock().await;
+    match result {
+        Ok(chain) => {
+            let log = format!("Got {} options on ${} expiring {}.", chain.contracts().len(), &symbol, expiration);
+            lock.log.push(log);
+            lock.chain_cache.insert((symbol, expiration), chain);
+        },
+        Err(err) => {
+            let context = format!("Failed to get options chain for ${} expiring {}", &symbol, expiration);
+            lock.log.push(describe_error(&context, &err));
+        }
+    }
+}
+
+/// Load the profile if there's no account number yet, then the account's
+/// balances, positions, orders and history, and quotes for what it holds.
+async fn background_fetch_account(app: Arc<Mutex<App>>) {
+    let accounts = Arc::clone(&app.lock().await.accounts);
+    let account_id = match resolve_account_id(Arc::clone(&app)).await {
+        Some(id) => id,
+        None => {
+            app.lock().await.account.loading = false;
+            return;
+        }
+    };
+
+    let (balances, positions, orders, history) = futures::join!(
+        accounts.get_balances(account_id.clone()),
+        accounts.get_positions(account_id.clone()),
+        accounts.get_orders(account_id.clone()),
+        accounts.get_account_history(account_id.clone())
+    );
+    let mut lock = app.lock().await;
+    lock.account.loading = false;
+    lock.account.account_number = Some(account_id.clone());
+    match balances {
+        Ok(balances) => lock.account.balances = balances.balances().cloned(),
+        Err(err) => lock.log.push(describe_error("Failed to get account balances", &err))
+    }
+    let mut tickers = vec![];
+    match positions {
+        Ok(positions) => {
+            tickers = positions.positions().iter().map(|p| p.symbol.clone()).collect();
+            lock.account.positions = StatefulList::with_list(positions.positions().to_vec());
+            if !tickers.is_empty() {
+                lock.account.positions.state.select(Some(0));
+            }
+        },
+        Err(err) => lock.log.push(describe_error("Failed to get account positions", &err))
+    }
+    match orders {
+        Ok(orders) => lock.account.orders = Some(orders),
+        Err(err) => lock.log.push(describe_error("Failed to get account orders", &err))
+    }
+    match history {
+        Ok(history) => lock.account.history = Some(history),
+        Err(err) => lock.log.push(describe_error("Failed to get account history", &err))
+    }
+    lock.log.push(format!("Loaded account {}.", account_id));
+    drop(lock);
+
+    if !tickers.is_empty() {
+        background_fetch_quotes(app, tickers).await;
+    }
+}
+
+/// The account to trade in: the one chosen at startup, or else the first
+/// on the user's profile. Failures are logged.
+async fn resolve_account_id(app: Arc<Mutex<App>>) -> Option<String> {
+    let accounts = {
+        let lock = app.lock().await;
+        if let Some(id) = &lock.account.account_number {
+            return Some(id.clone());
+        }
+        Arc::clone(&lock.accounts)
+    };
+    let result = accounts.get_profile().await;
+    let mut lock = app.lock().await;
+    match result {
+        Ok(profile) => {
+            lock.account.profile = profile.profile().cloned();
+            let first = profile.profile().and_then(|p| p.account.first()).map(|a| a.account_number.clone());
+            if first.is_none() {
+                lock.log.push("The profile has no brokerage accounts.".to_string());
+            }
+            lock.account.account_number = first.clone();
+            first
+        },
+        Err(err) => {
+            lock.log.push(describe_error("Failed to get user profile", &err));
+            None
+        }
+    }
+}
+
+async fn background_preview_order(app: Arc<Mutex<App>>, request: OrderRequest) {
+    let result = match resolve_account_id(Arc::clone(&app)).await {
+        Some(account_id) => {
+            let accounts = Arc::clone(&app.lock().await.accounts);
+            accounts.preview_order(account_id, request).await
+                .map_err(|err| describe_error("Preview failed", &err))
+        },
+        None => Err("No account to trade in".to_string())
+    };
+    let mut lock = app.lock().await;
+    // The ticket may have been closed or edited while we waited.
+    let ticket = match lock.ticket.as_mut() {
+        Some(ticket) if matches!(ticket.stage, TicketStage::Previewing) => ticket,
+        _ => return
+    };
+    ticket.stage = match result {
+        Ok(preview) if preview.preview().result => TicketStage::Previewed(preview.preview().clone()),
+        Ok(preview) => TicketStage::Failed(format!("Tradier won't accept this order ({})", preview.preview().status)),
+        Err(msg) => TicketStage::Failed(msg)
+    };
+}
+
+async fn background_place_order(app: Arc<Mutex<App>>, request: OrderRequest) {
+    let summary = request.summary();
+    let result = match resolve_account_id(Arc::clone(&app)).await {
+        Some(account_id) => {
+            let accounts = Arc::clone(&app.lock().await.accounts);
+            accounts.place_order(account_id, request).await
+                .map_err(|err| describe_error("Order failed", &err))
+        },
+        None => Err("No account to trade in".to_string())
+    };
+    let mut lock = app.lock().await;
+    let stage = match result {
+        Ok(confirmation) => {
+            let confirmation = confirmation.confirmation();
+            lock.log.push(format!("Placed order #{} ({}): {}.", confirmation.id, confirmation.status, summary));
+            TicketStage::Submitted(confirmation.id)
+        },
+        Err(msg) => {
+            lock.log.push(msg.clone());
+            TicketStage::Failed(msg)
+        }
+    };
+    if let Some(ticket) = lock.ticket.as_mut() {
+        ticket.stage = stage;
+    }
+    // Show the new order in the account view.
+    if !lock.account.loading {
+        lock.account.loading = true;
+        drop(lock);
+        background_fetch_account(app).await;
+    }
+}
+
+async fn background_fetch_quotes(app: Arc<Mutex<App>>, tickers: Vec<String>) {
+    let provider = Arc::clone(&app.lock().await.provider);
+    let result = provider.get_stock_quotes(tickers).await;
+    let mut lock = app.lock().await;
+    match result {
+        Ok(quotes) => {
+            for quote in quotes.quotes() {
+                lock.quote_cache.insert(symbology::normalize(&quote.symbol), quote.clone());
+            }
+        },
+        Err(err) => {
+            lock.log.push(describe_error("Failed to get quotes", &err));
+        }
+    }
+}
+
+/// How long to wait on a quiet stream before checking whether the symbols
+/// to stream have changed.
+const STREAM_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Most events applied under one lock of the app.
+const STREAM_BATCH: usize = 256;
+
+enum StreamEnd {
+    /// The quoted symbols changed, so the stream needs new ones.
+    Resubscribe,
+    /// The server hung up. `received` is whether anything came first.
+    Closed { received: bool },
+    /// A read failed, after events were `received` or not.
+    Failed { error: ApiError, received: bool }
+}
+
+/// Keep quotes current from the provider's event stream for as long as the
+/// app runs, reconnecting with backoff when the stream drops. A failure that
+/// won't go away by itself, like the sandbox not streaming at all, turns
+/// streaming off until the next start.
+async fn background_stream_quotes(app: Arc<Mutex<App>>) {
+    let mut reconnect = Reconnect::new(RetryPolicy {
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(60),
+        ..RetryPolicy::default()
+    });
+    loop {
+        let (provider, symbols) = {
+            let lock = app.lock().await;
+            (Arc::clone(&lock.provider), lock.streamable_symbols())
+        };
+        if symbols.is_empty() {
+            tokio::time::delay_for(STREAM_CHECK_INTERVAL).await;
+            continue;
+        }
+        match provider.stream_events(symbols.clone()).await {
+            Ok(events) => {
+                {
+                    let mut lock = app.lock().await;
+                    lock.streaming = true;
+                    lock.log.push(format!("Streaming quotes for {} symbols.", symbols.len()));
+                }
+                let end = pump_events(Arc::clone(&app), events, &symbols).await;
+                let mut lock = app.lock().await;
+                lock.streaming = false;
+                // A stream that ends before any events counts as a failure.
+                let delay = match end {
+                    StreamEnd::Resubscribe => continue,
+                    StreamEnd::Closed { received } => {
+                        lock.log.push("Quote stream closed, reconnecting.".to_string());
+                        reconnect.delay(received)
+                    },
+                    StreamEnd::Failed { error, received } => {
+                        lock.log.push(describe_error("Quote stream failed, reconnecting", &error));
+                        reconnect.delay(received)
+                    }
+                };
+                drop(lock);
+                tokio::time::delay_for(delay).await;
+            },
+            Err(err) if !err.is_transient() => {
+                let mut lock = app.lock().await;
+                return lock.log.push(describe_error("Quote streaming is off", &err));
+            },
+            Err(err) => {
+                app.lock().await.log.push(describe_error("Failed to open quote stream", &err));
+                tokio::time::delay_for(reconnect.delay(false)).await;
+            }
+        }
+    }
+}
+
+/// Apply events from `events` to the quote cache until the stream ends or
+/// the symbols worth streaming are no longer `symbols`.
+async fn pump_events(app: Arc<Mutex<App>>, mut events: EventStream, symbols: &[String]) -> StreamEnd {
+    let mut received = false;
+    loop {
+        let first = match tokio::time::timeout(STREAM_CHECK_INTERVAL, events.next()).await {
+            Ok(Some(event)) => event,
+            Ok(None) => return StreamEnd::Closed { received },
+            Err(_) if app.lock().await.streamable_symbols() != symbols => return StreamEnd::Resubscribe,
+            Err(_) => continue
+        };
+        // Take whatever else has already arrived, so a busy stream doesn't
+        // queue for the lock once per event.
+        let mut batch = vec![first];
+        while batch.len() < STREAM_BATCH {
+            match events.next().now_or_never() {
+                Some(Some(event)) => batch.push(event),
+                _ => break
+            }
+        }
+        let mut lock = app.lock().await;
+        for event in batch {
+            match event {
+                Ok(event) => {
+                    received = true;
+                    if let Some(quote) = event.symbol().and_then(|s| lock.quote_cache.get_mut(&symbology::normalize(s))) {
+                        event.apply(quote);
+                    }
+                },
+                Err(err) if err.kind() == ErrorKind::Deserialization => {
+                    lock.log.push(describe_error("Skipped a stream event", &err));
+                },
+                Err(error) => return StreamEnd::Failed { error, received }
+            }
+        }
+        if lock.streamable_symbols() != symbols {
+            return StreamEnd::Resubscribe;
+        }
+    }
+}
+
 async fn background_fetch_watchlist_quotes(app: Arc<Mutex<App>>) { 
+    let (provider, tickers) = {
+        let lock = app.lock().await;
+        (Arc::clone(&lock.provider), lock.watchlist.list.iter().map(|s| s.short_name()).collect())
+    };
+    let result = provider.get_stock_quotes(tickers).await;
     let mut lock = app.lock().await;
-    let symbols = &lock.watchlist.list;
-    let tickers = symbols.into_iter().map(|s| s.short_name()).collect();
-    if let Ok(quotes) = client::get_stock_quotes(tickers).await {
-        for quote in quotes.quotes() {
-            lock.quote_cache.insert(quote.symbol.clone(), quote.clone());
+    match result {
+        Ok(quotes) => {
+            for quote in quotes.quotes() {
+                lock.quote_cache.insert(symbology::normalize(&quote.symbol), quote.clone());
+            }
+            lock.log.push("Downloaded watchlist quotes".to_string());
+            if !quotes.unmatched_symbols().is_empty() {
+                let msg = format!("No quotes for unknown symbols: {}", quotes.unmatched_symbols().join(", "));
+                lock.log.push(msg);
+            }
+        },
+        Err(err) => {
+            lock.log.push(describe_error("Failed to get watchlist quotes", &err));
         }
-        lock.log.push("Downloaded watchlist quotes".to_string());
     }
 }
 
 async fn background_fetch_graph(app: Arc<Mutex<App>>, symbol: String) {
+    let range = app.lock().await.chart_range;
+    match range {
+        ChartRange::Intraday => background_fetch_intraday(app, symbol).await,
+        range => background_fetch_history(app, symbol, range).await
+    }
+}
+
+async fn background_fetch_intraday(app: Arc<Mutex<App>>, symbol: String) {
+    let provider;
+    let session_filter;
+    let interval;
+    let day;
+    let session;
     {
         let lock = app.lock().await;
-        let idx = lock.watchlist.state.selected().unwrap();
-        let symbol = lock.watchlist.list[idx].symbol.clone();
-        if let Some(_) = lock.graph_cache.get(&symbol) {
+        provider = Arc::clone(&lock.provider);
+        session_filter = lock.session_filter;
+        interval = lock.interval;
+        if lock.graph_cache.contains_key(&symbol) {
             return;
         }
+        day = util::last_market_open_day(&lock.calendar);
+        session = match session_filter {
+            SessionFilter::Open => lock.calendar.regular_session(day.naive_local()),
+            SessionFilter::All => lock.calendar.extended_session(day.naive_local())
+        }.unwrap();
     }
-    let start_day = util::last_market_open_day();
-    let start_date = start_day.and_hms(9,30, 0);
-    let end_day = util::last_market_open_day();
-    let end_date = end_day.and_hms(16, 0, 1);
-    let result = client::get_time_series_data(
-        symbol.to_string(), start_date, end_date, 5
+    // Pad the end by a second so the closing bar is included.
+    let start_date = day.and_time(session.start).unwrap();
+    let end_date = day.and_time(session.end).unwrap() + chrono::Duration::seconds(1);
+    let result = provider.get_time_series_data(
+        symbol.to_string(), start_date, end_date, interval, session_filter
     ).await;
     let mut lock = app.lock().await;
     match result {
         Ok(series) => {
             let log = format!("Got timeseries data for ${}.", &symbol);
             lock.log.push(log);
-            lock.graph_cache.insert(symbol.to_string(), series); 
+            if lock.session_filter == session_filter && lock.interval == interval {
+                lock.graph_cache.insert(symbol.to_string(), series);
+            }
+        },
+        Err(err) => {
+            let context = format!("Failed to get timeseries data for ${}", &symbol);
+            lock.log.push(describe_error(&context, &err));
+        }
+    }
+}
+
+async fn background_fetch_history(app: Arc<Mutex<App>>, symbol: String, range: ChartRange) {
+    let key = (symbol.clone(), range);
+    let provider;
+    let end;
+    {
+        let lock = app.lock().await;
+        provider = Arc::clone(&lock.provider);
+        if lock.history_cache.contains_key(&key) {
+            return;
+        }
+        end = util::last_market_open_day(&lock.calendar).naive_local();
+    }
+    let (interval, days) = match range {
+        ChartRange::OneMonth => (HistoryInterval::Daily, 31),
+        ChartRange::SixMonths => (HistoryInterval::Daily, 183),
+        ChartRange::OneYear => (HistoryInterval::Daily, 365),
+        _ => (HistoryInterval::Monthly, 5 * 365 + 1)
+    };
+    let start = end - chrono::Duration::days(days);
+    let result = provider.get_history(symbol.clone(), interval, start, end).await;
+    let mut lock = app.lock().await;
+    match result {
+        Ok(history) => {
+            let log = format!("Got {} history for ${}.", range.label(), &symbol);
+            lock.log.push(log);
+            lock.history_cache.insert(key, history);
         },
-        Err(_) => {
-            lock.log.push("Failed to get timeseries data".to_string());
+        Err(err) => {
+            let context = format!("Failed to get {} history for ${}", range.label(), &symbol);
+            lock.log.push(describe_error(&context, &err));
         }
     }
+}
+
+fn describe_error(context: &str, err: &ApiError) -> String {
+    let hint = match err.kind() {
+        ErrorKind::Unauthorized => " (check TRADIER_API_KEY)",
+        ErrorKind::RateLimited => " (rate limited, try again shortly)",
+        ErrorKind::BadSymbol => " (unknown symbol)",
+        _ => ""
+    };
+    format!("{}{}: {}", context, hint, err)
 }
\ No newline at end of file
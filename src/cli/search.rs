@@ -0,0 +1,104 @@
+use crate::data::Security;
+use crate::data::symbology;
+use crate::data::search::SymbolMatch;
+use crate::cli::ui::StatefulList;
+
+const MAX_LOCAL_MATCHES: usize = 10;
+const MAX_RESULTS: usize = 25;
+
+/// SearchState is the search prompt and the results of the last search.
+#[derive(Default)]
+pub struct SearchState {
+    pub query: String,
+    pub editing: bool,
+    pub results: StatefulList<SymbolMatch>
+}
+
+/// Directory securities with a symbol starting with `query`, or whose name
+/// contains it.
+pub fn local_matches(securities: &[Security], query: &str) -> Vec<SymbolMatch> {
+    let upper = query.to_uppercase();
+    let normalized = symbology::normalize(query);
+    let lower = query.to_lowercase();
+    securities.iter()
+        .filter(|s| {
+            s.symbol.starts_with(&normalized)
+                || s.symbols().iter().any(|symbol| symbol.starts_with(&upper))
+                || s.security_name.to_lowercase().contains(&lower)
+        })
+        .take(MAX_LOCAL_MATCHES)
+        .map(SymbolMatch::from)
+        .collect()
+}
+
+/// Combine matches from every source, dropping repeated symbols and putting
+/// an exact symbol match first.
+pub fn merge(query: &str, sources: Vec<Vec<SymbolMatch>>) -> Vec<SymbolMatch> {
+    let mut results: Vec<SymbolMatch> = vec![];
+    for m in sources.into_iter().flatten() {
+        if !results.iter().any(|r| symbology::same_symbol(&r.symbol, &m.symbol)) {
+            results.push(m);
+        }
+    }
+    results.sort_by_key(|m| !symbology::same_symbol(&m.symbol, query));
+    results.truncate(MAX_RESULTS);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::search::SecurityType;
+    use crate::data::symbols::NasdaqListed;
+
+    fn security(symbol: &str, name: &str) -> Security {
+        Security::from(format!("{}|{}|Q|N|N|100|N|N", symbol, name).parse::<NasdaqListed>().unwrap())
+    }
+
+    fn found(symbol: &str, security_type: SecurityType) -> SymbolMatch {
+        SymbolMatch { symbol: symbol.to_string(), exchange: "N".to_string(), security_type, description: String::new() }
+    }
+
+    fn symbols(matches: &[SymbolMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn local_matches_ignore_case() {
+        let securities = vec![security("AAPL", "Apple Inc."), security("AMD", "Advanced Micro Devices"), security("MSFT", "Microsoft Corporation")];
+        assert_eq!(symbols(&local_matches(&securities, "aa")), vec!["AAPL"]);
+        assert_eq!(symbols(&local_matches(&securities, "MICRO")), vec!["AMD", "MSFT"]);
+        assert!(local_matches(&securities, "zz").is_empty());
+    }
+
+    #[test]
+    fn local_matches_are_limited() {
+        let securities: Vec<Security> = (0..20).map(|i| security(&format!("A{}", i), "Any Corp")).collect();
+        assert_eq!(local_matches(&securities, "a").len(), MAX_LOCAL_MATCHES);
+    }
+
+    #[test]
+    fn merged_results_drop_repeats_across_sources() {
+        let local = vec![found("BRK.B", SecurityType::Stock), found("AAPL", SecurityType::Stock)];
+        let remote = vec![found("BRK/B", SecurityType::Stock), found("SPY", SecurityType::Etf), found("aapl", SecurityType::Stock)];
+        let merged = merge("x", vec![local, remote]);
+        assert_eq!(symbols(&merged), vec!["BRK.B", "AAPL", "SPY"]);
+    }
+
+    #[test]
+    fn an_exact_symbol_match_comes_first() {
+        let local = vec![found("SPYG", SecurityType::Etf), found("SPYD", SecurityType::Etf)];
+        let remote = vec![found("SPX", SecurityType::Index), found("SPY", SecurityType::Etf)];
+        let merged = merge("spy", vec![local, remote]);
+        assert_eq!(symbols(&merged), vec!["SPY", "SPYG", "SPYD", "SPX"]);
+    }
+
+    #[test]
+    fn merged_results_are_limited() {
+        let many: Vec<SymbolMatch> = (0..40).map(|i| found(&format!("X{}", i), SecurityType::Stock)).collect();
+        let merged = merge("X39", vec![many]);
+        assert_eq!(merged.len(), MAX_RESULTS);
+        // The exact match is sorted in before the cut.
+        assert_eq!(merged[0].symbol, "X39");
+    }
+}
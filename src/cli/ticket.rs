@@ -0,0 +1,476 @@
+use crate::api::order::{
+    Condition, ConditionalLeg, ConditionalOrder, EquityOrder, LegSide, OptionOrder, OptionSide, OrderDuration,
+    OrderRequest, OrderSide, OrderType
+};
+use crate::api::strategy::Strategy;
+use crate::data::account::{OrderPreview, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TicketField {
+    Side,
+    Quantity,
+    Type,
+    Price,
+    Stop,
+    Duration,
+    TakeProfit,
+    StopLoss
+}
+
+/// What the ticket is for.
+#[derive(Debug, Clone)]
+pub enum TicketKind {
+    /// A stock order. With a take-profit or stop-loss it goes in as an OTO or
+    /// OTOCO, so the exits are only working once the entry fills.
+    Equity,
+    /// A single option contract, bought or sold as `side`. `symbol` is the
+    /// underlying.
+    Option { option_symbol: String, side: OptionSide },
+    /// An OCO pair of exits for a position already held. `entry` is what was
+    /// paid per share, when the position says.
+    Bracket { option_symbol: Option<String>, exit: LegSide, multiplier: f64, entry: Option<f64> },
+    /// A spread from the options builder.
+    Multileg(Strategy)
+}
+
+/// Where the ticket is between filling it in and the order being placed.
+/// Orders are always previewed first, and only placed once the preview has
+/// been confirmed.
+#[derive(Debug, Clone)]
+pub enum TicketStage {
+    Editing,
+    Previewing,
+    Previewed(OrderPreview),
+    Submitting,
+    Submitted(u64),
+    Failed(String)
+}
+
+/// OrderTicket is the order entry modal.
+#[derive(Debug, Clone)]
+pub struct OrderTicket {
+    pub kind: TicketKind,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: String,
+    pub order_type: OrderType,
+    pub duration: OrderDuration,
+    pub price: String,
+    pub stop: String,
+    pub take_profit: String,
+    pub stop_loss: String,
+    pub focus: TicketField,
+    pub stage: TicketStage
+}
+
+impl OrderTicket {
+    pub fn new(symbol: String, side: OrderSide, quantity: u32) -> Self {
+        OrderTicket {
+            kind: TicketKind::Equity,
+            symbol,
+            side,
+            quantity: quantity.to_string(),
+            order_type: OrderType::Market,
+            duration: OrderDuration::Day,
+            price: String::new(),
+            stop: String::new(),
+            take_profit: String::new(),
+            stop_loss: String::new(),
+            focus: TicketField::Side,
+            stage: TicketStage::Editing
+        }
+    }
+
+    /// An order closing `position`: sold if long, bought back if short.
+    pub fn close(position: &Position) -> Self {
+        let long = position.quantity > 0.0;
+        let quantity = position.quantity.abs() as u32;
+        if !position.is_option() {
+            let side = if long { OrderSide::Sell } else { OrderSide::BuyToCover };
+            return OrderTicket::new(position.symbol.clone(), side, quantity);
+        }
+        let mut ticket = OrderTicket::new(position.underlying().to_string(), OrderSide::Sell, quantity);
+        ticket.kind = TicketKind::Option {
+            option_symbol: position.symbol.clone(),
+            side: if long { OptionSide::SellToClose } else { OptionSide::BuyToClose }
+        };
+        ticket.focus = TicketField::Quantity;
+        ticket
+    }
+
+    /// Take-profit and stop-loss exits for `position`, good till cancelled.
+    pub fn bracket(position: &Position) -> Self {
+        let long = position.quantity > 0.0;
+        let exit = match (position.is_option(), long) {
+            (false, true) => LegSide::Equity(OrderSide::Sell),
+            (false, false) => LegSide::Equity(OrderSide::BuyToCover),
+            (true, true) => LegSide::Option(OptionSide::SellToClose),
+            (true, false) => LegSide::Option(OptionSide::BuyToClose)
+        };
+        let quantity = position.quantity.abs();
+        let shares = quantity * position.multiplier();
+        let mut ticket = OrderTicket::new(position.underlying().to_string(), OrderSide::Sell, quantity as u32);
+        ticket.kind = TicketKind::Bracket {
+            option_symbol: if position.is_option() { Some(position.symbol.clone()) } else { None },
+            exit,
+            multiplier: position.multiplier(),
+            entry: if shares > 0.0 { Some(position.cost_basis.abs() / shares) } else { None }
+        };
+        ticket.duration = OrderDuration::Gtc;
+        ticket.focus = TicketField::TakeProfit;
+        ticket
+    }
+
+    /// An order for one of `strategy`, with the limit starting at its
+    /// midpoint price.
+    pub fn multileg(strategy: Strategy) -> Self {
+        let mut ticket = OrderTicket::new(strategy.underlying().to_string(), OrderSide::Buy, 1);
+        ticket.order_type = OrderType::Limit;
+        ticket.price = strategy.net_price().map_or(String::new(), |net| format!("{:.2}", net.abs()));
+        ticket.kind = TicketKind::Multileg(strategy);
+        ticket.focus = TicketField::Quantity;
+        ticket
+    }
+
+    /// The fields the ticket uses, top to bottom.
+    pub fn fields(&self) -> Vec<TicketField> {
+        match self.kind {
+            TicketKind::Bracket { .. } => {
+                return vec![TicketField::Quantity, TicketField::TakeProfit, TicketField::StopLoss, TicketField::Duration];
+            },
+            TicketKind::Multileg(_) => {
+                return vec![TicketField::Quantity, TicketField::Price, TicketField::Duration];
+            },
+            TicketKind::Equity | TicketKind::Option { .. } => {}
+        }
+        // An option ticket's side is fixed, and exits only go on stock.
+        let equity = matches!(self.kind, TicketKind::Equity);
+        let mut fields = if equity {
+            vec![TicketField::Side, TicketField::Quantity, TicketField::Type]
+        } else {
+            vec![TicketField::Quantity, TicketField::Type]
+        };
+        if self.order_type.needs_price() {
+            fields.push(TicketField::Price);
+        }
+        if self.order_type.needs_stop() {
+            fields.push(TicketField::Stop);
+        }
+        fields.push(TicketField::Duration);
+        if equity {
+            fields.push(TicketField::TakeProfit);
+            fields.push(TicketField::StopLoss);
+        }
+        fields
+    }
+
+    pub fn is_editable(&self) -> bool {
+        matches!(self.stage, TicketStage::Editing | TicketStage::Previewed(_) | TicketStage::Failed(_))
+    }
+
+    pub fn move_focus(&mut self, forward: bool) {
+        let fields = self.fields();
+        let index = fields.iter().position(|f| *f == self.focus).unwrap_or(0);
+        let index = if forward {
+            (index + 1) % fields.len()
+        } else {
+            (index + fields.len() - 1) % fields.len()
+        };
+        self.focus = fields[index];
+    }
+
+    /// Step the focused choice (side, type or duration) forwards or back.
+    pub fn cycle(&mut self, forward: bool) {
+        if !self.is_editable() {
+            return;
+        }
+        match self.focus {
+            TicketField::Side => self.side = step(&OrderSide::ALL, self.side, forward),
+            TicketField::Type => self.order_type = step(&OrderType::ALL, self.order_type, forward),
+            TicketField::Duration => self.duration = step(&OrderDuration::ALL, self.duration, forward),
+            _ => return
+        }
+        self.edited();
+    }
+
+    /// Type into the focused number field.
+    pub fn input(&mut self, c: char) {
+        if !self.is_editable() {
+            return;
+        }
+        let field = match self.focus {
+            TicketField::Quantity if c.is_ascii_digit() => &mut self.quantity,
+            TicketField::Price if c.is_ascii_digit() || c == '.' => &mut self.price,
+            TicketField::Stop if c.is_ascii_digit() || c == '.' => &mut self.stop,
+            TicketField::TakeProfit if c.is_ascii_digit() || c == '.' => &mut self.take_profit,
+            TicketField::StopLoss if c.is_ascii_digit() || c == '.' => &mut self.stop_loss,
+            _ => return
+        };
+        field.push(c);
+        self.edited();
+    }
+
+    pub fn backspace(&mut self) {
+        if !self.is_editable() {
+            return;
+        }
+        match self.focus {
+            TicketField::Quantity => self.quantity.pop(),
+            TicketField::Price => self.price.pop(),
+            TicketField::Stop => self.stop.pop(),
+            TicketField::TakeProfit => self.take_profit.pop(),
+            TicketField::StopLoss => self.stop_loss.pop(),
+            _ => return
+        };
+        self.edited();
+    }
+
+    /// Any change makes the last preview meaningless.
+    fn edited(&mut self) {
+        self.stage = TicketStage::Editing;
+        let fields = self.fields();
+        if !fields.contains(&self.focus) {
+            self.focus = if fields.contains(&TicketField::Type) { TicketField::Type } else { fields[0] };
+        }
+    }
+
+    pub fn request(&self) -> Result<OrderRequest, String> {
+        let quantity = self.quantity.parse().unwrap_or(0);
+        let take_profit = parse_price(&self.take_profit)?;
+        let stop_loss = parse_price(&self.stop_loss)?;
+        let request = match &self.kind {
+            TicketKind::Equity => {
+                let entry = EquityOrder {
+                    symbol: self.symbol.clone(),
+                    side: self.side,
+                    quantity,
+                    order_type: self.order_type,
+                    duration: self.duration,
+                    price: parse_price(&self.price)?,
+                    stop: parse_price(&self.stop)?
+                };
+                self.bracketed(entry, take_profit, stop_loss)?
+            },
+            TicketKind::Option { option_symbol, side } => OrderRequest::Option(OptionOrder {
+                symbol: self.symbol.clone(),
+                option_symbol: option_symbol.clone(),
+                side: *side,
+                quantity,
+                order_type: self.order_type,
+                duration: self.duration,
+                price: parse_price(&self.price)?,
+                stop: parse_price(&self.stop)?
+            }),
+            TicketKind::Bracket { option_symbol, exit, .. } => {
+                let (take_profit, stop_loss) = match (take_profit, stop_loss) {
+                    (Some(take_profit), Some(stop_loss)) => (take_profit, stop_loss),
+                    _ => return Err("A bracket needs both a take-profit and a stop-loss price".to_string())
+                };
+                let exit = |order_type, price, stop| ConditionalLeg {
+                    symbol: self.symbol.clone(),
+                    option_symbol: option_symbol.clone(),
+                    side: *exit,
+                    quantity,
+                    order_type,
+                    price,
+                    stop
+                };
+                OrderRequest::Conditional(ConditionalOrder {
+                    condition: Condition::Oco,
+                    legs: vec![
+                        exit(OrderType::Limit, Some(take_profit), None),
+                        exit(OrderType::Stop, None, Some(stop_loss))
+                    ],
+                    duration: self.duration
+                })
+            },
+            TicketKind::Multileg(strategy) => {
+                OrderRequest::Multileg(strategy.order(quantity, parse_price(&self.price)?, self.duration))
+            }
+        };
+        request.validate().map_err(|err| err.to_string())?;
+        Ok(request)
+    }
+
+    /// `entry` on its own, or with its exits attached: one exit makes an
+    /// OTO, both an OTOCO.
+    fn bracketed(&self, entry: EquityOrder, take_profit: Option<f64>, stop_loss: Option<f64>) -> Result<OrderRequest, String> {
+        if take_profit.is_none() && stop_loss.is_none() {
+            return Ok(OrderRequest::Equity(entry));
+        }
+        let exit_side = match entry.side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::SellShort => OrderSide::BuyToCover,
+            _ => return Err("Take-profit and stop-loss only go on orders that open a position".to_string())
+        };
+        let leg = |side, order_type, price, stop| ConditionalLeg {
+            symbol: entry.symbol.clone(),
+            option_symbol: None,
+            side: LegSide::Equity(side),
+            quantity: entry.quantity,
+            order_type,
+            price,
+            stop
+        };
+        let mut legs = vec![leg(entry.side, entry.order_type, entry.price, entry.stop)];
+        if let Some(take_profit) = take_profit {
+            legs.push(leg(exit_side, OrderType::Limit, Some(take_profit), None));
+        }
+        if let Some(stop_loss) = stop_loss {
+            legs.push(leg(exit_side, OrderType::Stop, None, Some(stop_loss)));
+        }
+        let condition = if legs.len() == 3 { Condition::Otoco } else { Condition::Oto };
+        Ok(OrderRequest::Conditional(ConditionalOrder { condition, legs, duration: entry.duration }))
+    }
+
+    /// What would be lost if the stop-loss fills, measured from the entry
+    /// price. Only known for limit entries and held positions.
+    pub fn risk_to_stop(&self) -> Option<f64> {
+        let stop_loss = parse_price(&self.stop_loss).ok()??;
+        let quantity: f64 = self.quantity.parse().ok()?;
+        let (entry, multiplier) = match &self.kind {
+            TicketKind::Equity if self.order_type.needs_price() => (parse_price(&self.price).ok()??, 1.0),
+            TicketKind::Bracket { entry, multiplier, .. } => ((*entry)?, *multiplier),
+            _ => return None
+        };
+        Some((entry - stop_loss).abs() * quantity * multiplier)
+    }
+}
+
+fn parse_price(s: &str) -> Result<Option<f64>, String> {
+    if s.is_empty() {
+        Ok(None)
+    } else {
+        s.parse::<f64>().map(Some).map_err(|_| format!("\"{}\" isn't a price", s))
+    }
+}
+
+fn step<T: Copy + PartialEq>(all: &[T], current: T, forward: bool) -> T {
+    let index = all.iter().position(|x| *x == current).unwrap_or(0);
+    if forward {
+        all[(index + 1) % all.len()]
+    } else {
+        all[(index + all.len() - 1) % all.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(symbol: &str, quantity: f64, cost_basis: f64) -> Position {
+        Position { id: None, symbol: symbol.to_string(), quantity, cost_basis, date_acquired: None }
+    }
+
+    fn limit(side: OrderSide, price: &str) -> OrderTicket {
+        let mut ticket = OrderTicket::new("SPY".to_string(), side, 10);
+        ticket.order_type = OrderType::Limit;
+        ticket.price = price.to_string();
+        ticket
+    }
+
+    fn conditional(request: OrderRequest) -> ConditionalOrder {
+        match request {
+            OrderRequest::Conditional(order) => order,
+            other => panic!("expected a conditional order, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn a_plain_ticket_is_an_equity_order() {
+        match limit(OrderSide::Buy, "310.5").request().unwrap() {
+            OrderRequest::Equity(order) => {
+                assert_eq!((order.side, order.quantity, order.price), (OrderSide::Buy, 10, Some(310.5)));
+            },
+            other => panic!("expected an equity order, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn tickets_are_checked_before_sending() {
+        assert_eq!(limit(OrderSide::Buy, "").request().unwrap_err(), "This order type needs a limit price");
+        assert_eq!(limit(OrderSide::Buy, "abc").request().unwrap_err(), "\"abc\" isn't a price");
+        let mut ticket = limit(OrderSide::Buy, "310");
+        ticket.quantity = String::new();
+        assert_eq!(ticket.request().unwrap_err(), "Quantity must be at least 1");
+    }
+
+    #[test]
+    fn one_exit_makes_an_oto() {
+        let mut ticket = limit(OrderSide::Buy, "310");
+        ticket.stop_loss = "300".to_string();
+        let order = conditional(ticket.request().unwrap());
+        assert_eq!(order.condition, Condition::Oto);
+        assert_eq!(order.legs[0].side, LegSide::Equity(OrderSide::Buy));
+        assert_eq!(order.legs[1].side, LegSide::Equity(OrderSide::Sell));
+        assert_eq!((order.legs[1].order_type, order.legs[1].stop), (OrderType::Stop, Some(300.0)));
+    }
+
+    #[test]
+    fn both_exits_make_an_otoco() {
+        let mut ticket = limit(OrderSide::SellShort, "310");
+        ticket.take_profit = "290".to_string();
+        ticket.stop_loss = "320".to_string();
+        let order = conditional(ticket.request().unwrap());
+        assert_eq!(order.condition, Condition::Otoco);
+        let sides: Vec<LegSide> = order.legs.iter().map(|leg| leg.side).collect();
+        assert_eq!(sides, vec![
+            LegSide::Equity(OrderSide::SellShort),
+            LegSide::Equity(OrderSide::BuyToCover),
+            LegSide::Equity(OrderSide::BuyToCover)
+        ]);
+        assert_eq!((order.legs[1].order_type, order.legs[1].price), (OrderType::Limit, Some(290.0)));
+        assert_eq!((order.legs[2].order_type, order.legs[2].stop), (OrderType::Stop, Some(320.0)));
+    }
+
+    #[test]
+    fn closing_orders_take_no_exits() {
+        for &side in &[OrderSide::Sell, OrderSide::BuyToCover] {
+            let mut ticket = limit(side, "310");
+            ticket.take_profit = "320".to_string();
+            assert_eq!(ticket.request().unwrap_err(), "Take-profit and stop-loss only go on orders that open a position");
+        }
+    }
+
+    #[test]
+    fn risk_is_measured_from_the_entry() {
+        let mut ticket = limit(OrderSide::Buy, "310");
+        assert_eq!(ticket.risk_to_stop(), None);
+        ticket.stop_loss = "300".to_string();
+        assert_eq!(ticket.risk_to_stop(), Some(100.0));
+        ticket.order_type = OrderType::Market;
+        assert_eq!(ticket.risk_to_stop(), None);
+
+        // Two contracts bought at $3 each, stopped at $2.
+        let mut bracket = OrderTicket::bracket(&position("SPY200717C00310000", 2.0, 600.0));
+        bracket.stop_loss = "2".to_string();
+        assert_eq!(bracket.risk_to_stop(), Some(200.0));
+    }
+
+    #[test]
+    fn closing_a_stock_position() {
+        let ticket = OrderTicket::close(&position("AAPL", 10.0, 3000.0));
+        assert!(matches!(ticket.kind, TicketKind::Equity));
+        assert_eq!((ticket.symbol.as_str(), ticket.side, ticket.quantity.as_str()), ("AAPL", OrderSide::Sell, "10"));
+        let ticket = OrderTicket::close(&position("TSLA", -5.0, -4750.0));
+        assert_eq!((ticket.side, ticket.quantity.as_str()), (OrderSide::BuyToCover, "5"));
+    }
+
+    #[test]
+    fn closing_an_option_position() {
+        let mut ticket = OrderTicket::close(&position("SPY200717C00310000", 2.0, 580.0));
+        assert!(!ticket.fields().contains(&TicketField::Side));
+        assert!(!ticket.fields().contains(&TicketField::TakeProfit));
+        ticket.order_type = OrderType::Limit;
+        ticket.price = "7.5".to_string();
+        match ticket.request().unwrap() {
+            OrderRequest::Option(order) => {
+                assert_eq!((order.symbol.as_str(), order.option_symbol.as_str()), ("SPY", "SPY200717C00310000"));
+                assert_eq!((order.side, order.quantity, order.price), (OptionSide::SellToClose, 2, Some(7.5)));
+            },
+            other => panic!("expected an option order, got {:?}", other)
+        }
+        let short = OrderTicket::close(&position("SPY200717P00300000", -1.0, -250.0));
+        assert!(matches!(short.kind, TicketKind::Option { side: OptionSide::BuyToClose, .. }));
+    }
+}
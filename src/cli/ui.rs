@@ -1,17 +1,30 @@
 use std::io;
 
+use chrono::NaiveDate;
+
 use termion::raw::IntoRawMode;
 use tui::{
     backend::{Backend, TermionBackend}, 
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols,
-    widgets::{Widget, GraphType, Dataset, Block, Borders, List, Paragraph, ListState, Text, Chart, Axis},
+    widgets::{GraphType, Dataset, Block, Borders, Clear, List, Paragraph, ListState, Text, Chart, Axis, Row, Table},
     Frame
 };
 
+use crate::api::SessionFilter;
+use crate::api::retry::BreakerState;
 use crate::cli::App;
-use crate::data::{self, Symbol, Quote};
+use crate::api::strategy::{Risk, Strategy};
+use crate::cli::chain;
+use crate::cli::ticket::{OrderTicket, TicketField, TicketKind, TicketStage};
+use crate::data::{self, Quote};
+use crate::data::clock::{MarketClock, MarketState};
+use crate::data::account::Position;
+use crate::data::chain::OptionContract;
+use crate::data::search::SymbolMatch;
+use crate::data::watchlist::{EntrySource, WatchlistEntry};
+use crate::util;
 
 pub type Terminal = tui::Terminal<TermionBackend<termion::raw::RawTerminal<io::Stdout>>>;
 
@@ -19,7 +32,7 @@ pub trait Listable {
     fn short_name(&self) -> String;
 }
 
-impl Listable for Symbol {
+impl Listable for WatchlistEntry {
     fn short_name(&self) -> String { self.symbol.clone() }
 }
 
@@ -27,6 +40,19 @@ impl Listable for data::Option {
     fn short_name(&self) -> String { self.underlying_symbol.clone() }
 }
 
+impl Listable for SymbolMatch {
+    fn short_name(&self) -> String { self.symbol.clone() }
+}
+
+impl Listable for Position {
+    fn short_name(&self) -> String { self.symbol.clone() }
+}
+
+// Option expirations.
+impl Listable for NaiveDate {
+    fn short_name(&self) -> String { self.format("%m/%d").to_string() }
+}
+
 #[derive(Clone)]
 pub struct StatefulList<T: Listable> { 
     pub list: Vec<T>,
@@ -82,13 +108,38 @@ impl<T: Listable> StatefulList<T> {
 }
 
 pub enum ViewContext {
-    Watchlist
+    Watchlist,
+    Search,
+    Options,
+    Account
+}
+
+/// How far back the graph looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChartRange {
+    Intraday,
+    OneMonth,
+    SixMonths,
+    OneYear,
+    FiveYears
+}
+
+impl ChartRange {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChartRange::Intraday => "1D",
+            ChartRange::OneMonth => "1M",
+            ChartRange::SixMonths => "6M",
+            ChartRange::OneYear => "1Y",
+            ChartRange::FiveYears => "5Y"
+        }
+    }
 }
 
 pub fn initialize_terminal() -> Result<Terminal, io::Error> {
     let stdout = io::stdout().into_raw_mode()?;
     let backend = TermionBackend::new(stdout);
-    Ok(Terminal::new(backend)?)
+    Terminal::new(backend)
 }
 
 pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
@@ -106,13 +157,80 @@ pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     draw_header(f, app, chunks[0]);
     draw_main_area(f, app, chunks[1]);
     draw_log_section(f, app, chunks[2]);
+    if let Some(ticket) = &app.ticket {
+        draw_ticket(f, ticket, app.accounts.is_live(), f.size());
+    }
 }
     
 fn draw_header<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let title = if app.search.editing {
+        format!("Search: {}_", app.search.query)
+    } else {
+        app.title.clone()
+    };
     let block = Block::default()
-        .title(&app.title)
+        .title(&title)
         .borders(Borders::ALL);
-    f.render_widget(block, area);
+    let mut text = vec![];
+    if let Some(clock) = &app.clock {
+        text.extend(clock_text(clock));
+        text.push(Text::raw(" | "));
+    }
+    text.push(Text::raw(rate_budget_text(app)));
+    if app.streaming {
+        text.push(Text::raw(" | "));
+        text.push(Text::styled("Streaming", Style::default().fg(Color::Green)));
+    }
+    let paragraph = Paragraph::new(text.iter()).block(block).wrap(false);
+    f.render_widget(paragraph, area);
+}
+
+/// Session state, exchange time and a countdown to the next change, e.g.
+/// "Open | 14:02:11 ET | Closes in 1h 57m 49s".
+fn clock_text(clock: &MarketClock) -> Vec<Text<'static>> {
+    let now = util::now();
+    let color = match clock.state {
+        MarketState::Open => Color::Green,
+        MarketState::Premarket | MarketState::Postmarket => Color::Yellow,
+        MarketState::Closed => Color::Red
+    };
+    let change = match clock.next_state {
+        MarketState::Premarket => "Pre-market opens",
+        MarketState::Open => "Opens",
+        MarketState::Postmarket => "Closes",
+        MarketState::Closed => "After hours end"
+    };
+    let remaining = (clock.next_change - now).num_seconds().max(0);
+    let countdown = match (remaining / 3600, remaining % 3600 / 60, remaining % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, s) => format!("{}h {}m {}s", h, m, s)
+    };
+    vec![
+        Text::styled(clock.state.label(), Style::default().fg(color).modifier(Modifier::BOLD)),
+        Text::raw(format!(" | {} ET | {} in {}", now.format("%H:%M:%S"), change, countdown))
+    ]
+}
+
+fn rate_budget_text(app: &App) -> String {
+    match app.provider.breaker_state() {
+        Some(BreakerState::Open { until }) => {
+            let retry_in = (until - chrono::Utc::now()).num_seconds().max(0);
+            return format!("Source: {} | Circuit open, retrying in {}s", app.provider.name(), retry_in);
+        },
+        Some(BreakerState::HalfOpen) => {
+            return format!("Source: {} | Circuit half-open, checking API", app.provider.name());
+        },
+        _ => {}
+    }
+    match app.provider.rate_budget() {
+        Some(budget) => {
+            let resets_in = (budget.expiry - chrono::Utc::now()).num_seconds().max(0);
+            format!("Source: {} | API budget: {}/{} (resets in {}s)",
+                app.provider.name(), budget.available, budget.allowed, resets_in)
+        },
+        None => format!("Source: {}", app.provider.name())
+    }
 }
 
 fn draw_main_area<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
@@ -131,127 +249,540 @@ fn draw_main_area<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
                     Constraint::Min(0)
                 ].as_ref())
                 .split(chunks[0]);
-            draw_watchlist(f, app, chunks[0]);
+            match app.active_context {
+                ViewContext::Watchlist => draw_watchlist(f, app, chunks[0]),
+                ViewContext::Search => draw_search_results(f, app, chunks[0]),
+                ViewContext::Options => draw_expirations(f, app, chunks[0]),
+                ViewContext::Account => draw_positions(f, app, chunks[0])
+            }
             draw_quote_section(f, app, chunks[1]);
     }
-    draw_graph_section(f, app, chunks[1]);
+    match app.active_context {
+        ViewContext::Options => draw_chain_section(f, app, chunks[1]),
+        ViewContext::Account => draw_account_section(f, app, chunks[1]),
+        _ => draw_graph_section(f, app, chunks[1])
+    }
 }
 
 fn draw_watchlist<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
-    let items = app.watchlist.list.iter().map(|i| {
-        let title = i.short_name();
-        Text::raw(title)
+    let title = watchlist_title(app);
+    // Entries the directory and the lookup can't vouch for are dimmed.
+    let items = app.watchlist.list.iter().map(|i| match i.source {
+        EntrySource::Unverified => Text::styled(format!("{} ?", i.short_name()), Style::default().fg(Color::DarkGray)),
+        EntrySource::NotFound => Text::styled(format!("{} !", i.short_name()), Style::default().fg(Color::Red)),
+        _ => Text::raw(i.short_name())
     });
     let tasks = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Watchlist"))
+        .block(Block::default().borders(Borders::ALL).title(&title))
         .highlight_style(Style::default().fg(Color::Yellow).modifier(Modifier::BOLD))
         .highlight_symbol("> ");
     f.render_stateful_widget(tasks, area, &mut app.watchlist.state);
 }
 
+/// The showing watchlist's name, and where it is among the saved ones.
+fn watchlist_title(app: &App) -> String {
+    let name = &app.watchlist_sync.name;
+    let watchlists = &app.watchlists.watchlists;
+    match watchlists.iter().position(|w| w.name == *name) {
+        Some(index) if watchlists.len() > 1 => format!("Watchlist: {} ({}/{})", name, index + 1, watchlists.len()),
+        _ => format!("Watchlist: {}", name)
+    }
+}
+
+fn draw_search_results<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let items = app.search.results.list.iter().map(|i| Text::raw(i.short_name()));
+    let results = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Search"))
+        .highlight_style(Style::default().fg(Color::Yellow).modifier(Modifier::BOLD))
+        .highlight_symbol("> ");
+    f.render_stateful_widget(results, area, &mut app.search.results.state);
+}
+
+fn draw_expirations<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let items = app.chain.expirations.list.iter().map(|i| Text::raw(i.short_name()));
+    let expirations = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Expiry"))
+        .highlight_style(Style::default().fg(Color::Yellow).modifier(Modifier::BOLD))
+        .highlight_symbol("> ");
+    f.render_stateful_widget(expirations, area, &mut app.chain.expirations.state);
+}
+
+fn draw_positions<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let items = app.account.positions.list.iter().map(|i| Text::raw(i.short_name()));
+    let positions = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Positions"))
+        .highlight_style(Style::default().fg(Color::Yellow).modifier(Modifier::BOLD))
+        .highlight_symbol("> ");
+    f.render_stateful_widget(positions, area, &mut app.account.positions.state);
+}
+
 fn draw_quote_section<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
     let symbol = app.selected_ticker();
     let text = quote_section_text(app.get_quote(symbol));
+    // Search results can be indices, funds or OTC names, so say which.
+    let title = match app.active_context {
+        ViewContext::Search => app.search.results.state.selected()
+            .map(|i| &app.search.results.list[i])
+            .map_or("Quote".to_string(), |m| format!("Quote ({}, exchange {})", m.security_type.label(), m.exchange)),
+        ViewContext::Watchlist => app.watchlist.state.selected()
+            .and_then(|i| app.watchlist.list.get(i))
+            .map_or("Quote".to_string(), |entry| match &entry.source {
+                EntrySource::Directory(s) => format!("Quote ({}{})", s.market(), if s.etf { ", ETF" } else { "" }),
+                EntrySource::Lookup(m) => format!("Quote ({}, exchange {})", m.security_type.label(), m.exchange),
+                EntrySource::NotFound => "Quote (not found by lookup)".to_string(),
+                EntrySource::Unverified => "Quote (unverified)".to_string()
+            }),
+        _ => "Quote".to_string()
+    };
     let block: Block = Block::default()
         .borders(Borders::ALL)
-        .title("Quote");
+        .title(&title);
     let paragraph = Paragraph::new(text.iter()).block(block).wrap(false);
     f.render_widget(paragraph, area);
 }
 
-fn quote_section_text(quote: Option<&Quote>) -> Vec<Text> { 
+fn quote_section_text(quote: Option<&Quote>) -> Vec<Text<'_>> { 
     let mut text = vec![];
     text.push(Text::styled(
         if let Some(q) = quote { q.description.to_string() } else { "...".to_string() },
         Style::default().modifier(Modifier::BOLD)
     ));
     text.push(Text::raw("\n\nLast Price: $"));
-    text.push(Text::raw(if let Some(q) = quote { format!("{:.2}", q.last) } else { "...".to_string() }));
+    text.push(quote_field(quote, |q| q.last.map(|l| format!("{:.2}", l))));
     text.push(Text::raw("\nVolume: "));
-    text.push(Text::raw(if let Some(q) = quote { q.volume.to_string() } else { "...".to_string() }));
-    text.push(Text::raw("\n\nBid:"));
-    text.push(Text::raw(format!(" ${} ({})", 
-        if let Some(q) = quote { format!("{:.2}", q.bid) } else { "...".to_string() },
-        if let Some(q) = quote { q.bid_size.to_string() } else { "...".to_string() }))
-    );
-    text.push(Text::raw("\nAsk:"));
-    text.push(Text::raw(format!(" ${} ({})", 
-        if let Some(q) = quote { format!("{:.2}", q.ask) } else { "...".to_string() },
-        if let Some(q) = quote { q.ask_size.to_string() } else { "...".to_string() }))
-    );
+    text.push(quote_field(quote, |q| q.volume.map(|v| v.to_string())));
+    text.push(Text::raw("\n\nBid: $"));
+    text.push(quote_field(quote, |q| q.bid.map(|b| format!("{:.2}", b))));
+    text.push(Text::raw(" ("));
+    text.push(quote_field(quote, |q| q.bid_size.map(|s| s.to_string())));
+    text.push(Text::raw(")"));
+    text.push(Text::raw("\nAsk: $"));
+    text.push(quote_field(quote, |q| q.ask.map(|a| format!("{:.2}", a))));
+    text.push(Text::raw(" ("));
+    text.push(quote_field(quote, |q| q.ask_size.map(|s| s.to_string())));
+    text.push(Text::raw(")"));
 
     text.push(Text::raw("\n\nChange "));
     text.push(Text::styled(format!("${} : {}%", 
-        if let Some(q) = quote { format!("{:.2}", q.change_points) } else { "...".to_string() },
-        if let Some(q) = quote { q.change_percentage.to_string() } else { "...".to_string() }),
-        if let Some(q) = quote { 
-            if q.change_points > 0.0 { 
-                Style::default().fg(Color::Green)
-            } else if q.change_points == 0.0 {
-                Style::default()
-            } else {
-                Style::default().fg(Color::Red)
-            }
-        } else { Style::default() }
+        quote.map_or("...".to_string(), |q| q.change_points.map_or("nil".to_string(), |c| format!("{:.2}", c))),
+        quote.map_or("...".to_string(), |q| q.change_percentage.map_or("nil".to_string(), |c| c.to_string()))),
+        match quote.and_then(|q| q.change_points) {
+            Some(c) if c > 0.0 => Style::default().fg(Color::Green),
+            Some(c) if c < 0.0 => Style::default().fg(Color::Red),
+            _ => Style::default()
+        }
     ));
     text.push(Text::raw("\nOpen: "));
-    text.push(Text::styled(
-        quote.map_or("...".to_string(), |q| {
-            q.open.map_or("nil".to_string(), |o| format!("{:.2}", o))
-        }), quote.map_or(Style::default(), |q| {
-            q.open.map_or(Style::default().fg(Color::Magenta), |_| Style::default())
-        })
-    ));
+    text.push(quote_field(quote, |q| q.open.map(|o| format!("{:.2}", o))));
     text.push(Text::raw("\nClose: "));
-    text.push(Text::styled(
-        quote.map_or("...".to_string(), |q| {
-            q.close.map_or("nil".to_string(), |c| format!("{:.2}", c))
-        }), quote.map_or(Style::default(), |q| {
-            q.close.map_or(Style::default().fg(Color::Magenta), |_| Style::default())
-        })
-    ));
+    text.push(quote_field(quote, |q| q.close.map(|c| format!("{:.2}", c))));
     text.push(Text::raw("\n\n52 Week High: $"));
-    text.push(Text::raw(if let Some(q) = quote { format!("{:.2}", q.week_52_high) } else { "...".to_string() }));
+    text.push(quote_field(quote, |q| q.week_52_high.map(|h| format!("{:.2}", h))));
     text.push(Text::raw("\n52 Week Low: $"));
-    text.push(Text::raw(if let Some(q) = quote { format!("{:.2}", q.week_52_low) } else { "...".to_string() }));
+    text.push(quote_field(quote, |q| q.week_52_low.map(|l| format!("{:.2}", l))));
     text
 }
 
+/// A quote field, "..." while the quote is loading and a magenta "nil" when
+/// Tradier has no value for it.
+fn quote_field<'a, F>(quote: Option<&Quote>, field: F) -> Text<'a>
+where
+    F: Fn(&Quote) -> Option<String>
+{
+    match quote.map(field) {
+        Some(Some(value)) => Text::raw(value),
+        Some(None) => Text::styled("nil", Style::default().fg(Color::Magenta)),
+        None => Text::raw("...")
+    }
+}
+
 fn draw_graph_section<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
-    // let dataset: Dataset;
+    let title = match (app.chart_range, app.session_filter) {
+        (ChartRange::Intraday, SessionFilter::Open) => format!("Graph (1D, {})", app.interval.as_str()),
+        (ChartRange::Intraday, SessionFilter::All) => format!("Graph (1D, {}, extended hours)", app.interval.as_str()),
+        (range, _) => match app.get_history(app.selected_ticker()).and_then(|h| h.bars().last()) {
+            Some(bar) => format!("Graph ({}) | {} O {:.2} H {:.2} L {:.2} C {:.2} V {}",
+                range.label(), bar.date, bar.open, bar.high, bar.low, bar.close, bar.volume),
+            None => format!("Graph ({})", range.label())
+        }
+    };
     let block = Block::default()
         .borders(Borders::ALL)
-        .title("Graph");
+        .title(&title);
 
     let selected_symbol = app.selected_ticker();
-    let graph_data = app.graph_cache.get(selected_symbol);
-    if let Some(timeseries) = graph_data {
-        let data = timeseries.to_graph_data(area.width);
-        let color = if timeseries.went_up() { Color::Green } else { Color::Red };
-        let dataset = &[Dataset::default()
-            .graph_type(GraphType::Line)
-            .marker(symbols::Marker::Braille)
-            .style(Style::default().fg(color))
-            .data(&data[..])];
-        let (min_bound, max_bound) = timeseries.min_max();
-        let labels = &[format!("{:.2}", min_bound),
-            format!("{:.2}", (max_bound + min_bound) * 0.5),
-            format!("{:.2}", max_bound)];
-        let chart = Chart::default()
-            .block(block)
+    let (data, (min_bound, max_bound), went_up, x_labels) = match app.chart_range {
+        ChartRange::Intraday => match app.graph_cache.get(selected_symbol) {
+            Some(timeseries) if !timeseries.is_empty() => (
+                timeseries.to_graph_data(area.width),
+                timeseries.min_max(),
+                timeseries.went_up(),
+                timeseries.time_labels(5)
+            ),
+            Some(_) => return draw_graph_message(f, block, area, format!("No trades for ${} in this session.", selected_symbol)),
+            None => return f.render_widget(block, area)
+        },
+        range => match app.get_history(selected_symbol) {
+            Some(history) if !history.is_empty() => (
+                history.to_graph_data(area.width),
+                history.min_max(),
+                history.went_up(),
+                history.date_labels(5)
+            ),
+            Some(_) => return draw_graph_message(f, block, area, format!("No {} history for ${}.", range.label(), selected_symbol)),
+            None => return f.render_widget(block, area)
+        }
+    };
+
+    let color = if went_up { Color::Green } else { Color::Red };
+    let dataset = &[Dataset::default()
+        .graph_type(GraphType::Line)
+        .marker(symbols::Marker::Braille)
+        .style(Style::default().fg(color))
+        .data(&data[..])];
+    let labels = &[format!("{:.2}", min_bound),
+        format!("{:.2}", (max_bound + min_bound) * 0.5),
+        format!("{:.2}", max_bound)];
+    let chart = Chart::default()
+        .block(block)
         .x_axis(Axis::default()
             .style(Style::default().fg(Color::Gray))
             .bounds([0.0, area.width as f64])
-            .labels(data::series::TIME_MARKERS))
+            .labels(&x_labels))
         .y_axis(Axis::default()
             .style(Style::default().fg(Color::Gray))
             .bounds([min_bound, max_bound])
             .labels(labels))
         .datasets(dataset);
-        f.render_widget(chart, area);
+    f.render_widget(chart, area);
+}
+
+/// Calls on the left, puts on the right, centered on the strike nearest the
+/// underlying's last price. With the options builder open, the strikes of
+/// the spread are marked and it's summarized underneath.
+fn draw_chain_section<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let symbol = app.selected_ticker();
+    let expiration = app.chain.selected_expiration();
+    let title = match expiration {
+        Some(date) => format!("Options on ${} expiring {} (calls | puts)", symbol, date.format("%b %d, %Y")),
+        None => format!("Options on ${}", symbol)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(&title);
+    let chain = match expiration.and_then(|date| app.get_chain(symbol, date)) {
+        Some(chain) if !chain.is_empty() => chain,
+        Some(_) => return draw_graph_message(f, block, area, format!("No contracts for ${} at this expiration.", symbol)),
+        None => return f.render_widget(block, area)
+    };
+
+    let strategy = app.strategy();
+    let area = match &app.chain.builder {
+        Some(_) => {
+            let summary_height = strategy.as_ref().map_or(0, |s| s.legs.len() as u16) + 4;
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(5), Constraint::Length(summary_height)].as_ref())
+                .split(area);
+            draw_strategy_summary(f, app, strategy.as_ref(), chunks[1]);
+            chunks[0]
+        },
+        None => area
+    };
+
+    let strikes = chain.by_strike();
+    let last = app.get_quote(symbol).and_then(|q| q.last).map(|l| l as f64);
+    let at_the_money = chain::at_the_money(chain, last);
+    let center = app.chain.builder.and_then(|b| b.center).or(at_the_money);
+    // Borders and the header row take four lines.
+    let visible = (area.height as usize).saturating_sub(4).max(1);
+    let first = center.unwrap_or(0).saturating_sub(visible / 2).min(strikes.len().saturating_sub(visible));
+
+    let rows = strikes.iter().enumerate().skip(first).take(visible).map(|(i, (strike, call, put))| {
+        let marker = |contract: Option<&OptionContract>| strategy.as_ref()
+            .and_then(|s| s.legs.iter().find(|leg| Some(&leg.contract.symbol) == contract.map(|c| &c.symbol)))
+            .map_or(" ", |leg| if leg.side.is_buy() { "+" } else { "-" });
+        let mut cells = contract_cells(*call);
+        cells.push(format!("{}{:>8.2}{}", marker(*call), strike, marker(*put)));
+        cells.extend(contract_cells(*put));
+        let on_strategy = strategy.as_ref()
+            .is_some_and(|s| s.legs.iter().any(|leg| leg.contract.strike == *strike));
+        let style = if on_strategy {
+            Style::default().fg(Color::Cyan).modifier(Modifier::BOLD)
+        } else if Some(i) == at_the_money {
+            Style::default().fg(Color::Yellow).modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        Row::StyledData(cells.into_iter(), style)
+    });
+    let header = ["Bid", "Ask", "OI", "IV", "Delta", "  Strike", "Bid", "Ask", "OI", "IV", "Delta"];
+    let mut widths = [Constraint::Length(7); 11];
+    widths[5] = Constraint::Length(10);
+    let table = Table::new(header.iter(), rows)
+        .block(block)
+        .header_style(Style::default().fg(Color::Gray).modifier(Modifier::BOLD))
+        .widths(&widths)
+        .column_spacing(1);
+    f.render_widget(table, area);
+}
+
+fn draw_strategy_summary<B: Backend>(f: &mut Frame<B>, app: &App, strategy: Option<&Strategy>, area: Rect) {
+    let builder = match app.chain.builder {
+        Some(builder) => builder,
+        None => return
+    };
+    let direction = if builder.buy { "Long" } else { "Short" };
+    let title = format!("{} {} (s: strategy  b: long/short  Left/Right: strike  +/-: width  t: order)",
+        direction, builder.kind.label().to_lowercase());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(&title);
+    let strategy = match strategy {
+        Some(strategy) => strategy,
+        None => return draw_graph_message(f, block, area, "The chain doesn't have the strikes for this spread.".to_string())
+    };
+    let mut text = vec![];
+    for leg in &strategy.legs {
+        let mid = leg.mid().map_or("-".to_string(), |mid| format!("{:.2}", mid));
+        text.push(Text::raw(format!("{:<13} {:<22} @ {}\n", leg.side.as_str(), leg.contract.symbol, mid)));
     }
+    text.extend(strategy_cost_text(strategy, 1.0));
+    let paragraph = Paragraph::new(text.iter()).block(block).wrap(false);
+    f.render_widget(paragraph, area);
+}
 
-        
+/// Net debit or credit and max risk for `quantity` of `strategy`.
+fn strategy_cost_text<'a>(strategy: &Strategy, quantity: f64) -> Vec<Text<'a>> {
+    let multiplier = strategy.multiplier() * quantity;
+    let net = match strategy.net_price() {
+        Some(net) if net > 0.0 => Text::styled(format!("Net debit: {:.2} (${:.2})", net, net * multiplier),
+            Style::default().fg(Color::Red)),
+        Some(net) => Text::styled(format!("Net credit: {:.2} (${:.2})", -net, -net * multiplier),
+            Style::default().fg(Color::Green)),
+        None => Text::raw("Net price: -")
+    };
+    let risk = match strategy.max_risk() {
+        Some(Risk::Limited(risk)) => format!("  Max risk: ${:.2}", risk * quantity),
+        Some(Risk::Unlimited) => "  Max risk: unlimited".to_string(),
+        None => "  Max risk: -".to_string()
+    };
+    vec![net, Text::styled(risk, Style::default().modifier(Modifier::BOLD))]
+}
+
+fn contract_cells(contract: Option<&OptionContract>) -> Vec<String> {
+    let price = |p: Option<f64>| p.map_or("-".to_string(), |p| format!("{:.2}", p));
+    let contract = match contract {
+        Some(contract) => contract,
+        None => return vec!["".to_string(); 5]
+    };
+    let greeks = contract.greeks.as_ref();
+    vec![
+        price(contract.bid),
+        price(contract.ask),
+        contract.open_interest.map_or("-".to_string(), |oi| oi.to_string()),
+        greeks.and_then(|g| g.mid_iv).map_or("-".to_string(), |iv| format!("{:.1}%", iv * 100.0)),
+        greeks.and_then(|g| g.delta).map_or("-".to_string(), |d| format!("{:.2}", d))
+    ]
+}
+
+/// Balances, positions marked to the latest quotes, open orders and recent
+/// activity.
+fn draw_account_section<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let account = &app.account;
+    let title = match (&account.account_number, &account.profile) {
+        (Some(number), Some(profile)) => match profile.find_account(number) {
+            Some(found) => format!("Account {} {} ({})", number, found.account_type.label(), profile.name),
+            None => format!("Account {} ({})", number, profile.name)
+        },
+        (Some(number), None) => format!("Account {}", number),
+        _ => "Account".to_string()
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(&title);
+    let balances = match &account.balances {
+        Some(balances) => balances,
+        None if account.loading => return draw_graph_message(f, block, area, "Loading account...".to_string()),
+        None => return f.render_widget(block, area)
+    };
+    let open_orders = account.orders.as_ref().map_or(vec![], |o| o.open_orders());
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),
+            Constraint::Min(4),
+            Constraint::Length((open_orders.len() as u16 + 3).min(8)),
+            Constraint::Length(7)
+        ].as_ref())
+        .split(area);
+
+    let money = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("${:.2}", v));
+    let text = [
+        Text::raw(format!("Equity: {}  Market value: {}  Cash: {}  Buying power: {}\n",
+            money(Some(balances.total_equity)), money(Some(balances.market_value)), money(Some(balances.total_cash)),
+            money(balances.buying_power()))),
+        Text::raw("Open P&L: "),
+        gain_text(balances.open_pl),
+        Text::raw("  Closed P&L: "),
+        gain_text(balances.close_pl)
+    ];
+    let paragraph = Paragraph::new(text.iter()).block(block).wrap(false);
+    f.render_widget(paragraph, chunks[0]);
+
+    let rows = account.positions.list.iter().map(|position| {
+        let last = app.get_quote(&position.symbol).and_then(|q| q.last).map(|l| l as f64);
+        let gain = last.map(|l| position.gain(l));
+        let style = match gain {
+            Some(g) if g > 0.0 => Style::default().fg(Color::Green),
+            Some(g) if g < 0.0 => Style::default().fg(Color::Red),
+            _ => Style::default()
+        };
+        Row::StyledData(vec![
+            position.symbol.clone(),
+            format!("{}", position.quantity),
+            money(Some(position.cost_basis)),
+            money(last),
+            money(last.map(|l| position.market_value(l))),
+            gain.map_or("-".to_string(), |g| format!("{:+.2}", g))
+        ].into_iter(), style)
+    });
+    let header = ["Symbol", "Qty", "Cost", "Last", "Value", "P&L"];
+    let widths = [Constraint::Length(19), Constraint::Length(7), Constraint::Length(11),
+        Constraint::Length(10), Constraint::Length(11), Constraint::Length(10)];
+    let table = Table::new(header.iter(), rows)
+        .block(Block::default().borders(Borders::ALL).title("Positions"))
+        .header_style(Style::default().fg(Color::Gray).modifier(Modifier::BOLD))
+        .widths(&widths)
+        .column_spacing(1);
+    f.render_widget(table, chunks[1]);
+
+    let rows = open_orders.iter().map(|order| Row::Data(vec![
+        order.display_symbol().to_string(),
+        order.side.clone(),
+        format!("{}/{}", order.exec_quantity.unwrap_or(0.0), order.quantity),
+        order.order_type.clone(),
+        order.price.or(order.stop_price).map_or("-".to_string(), |p| format!("{:.2}", p)),
+        order.duration.clone()
+    ].into_iter()));
+    let header = ["Symbol", "Side", "Filled", "Type", "Price", "Duration"];
+    let widths = [Constraint::Length(19), Constraint::Length(14), Constraint::Length(9),
+        Constraint::Length(11), Constraint::Length(9), Constraint::Length(8)];
+    let table = Table::new(header.iter(), rows)
+        .block(Block::default().borders(Borders::ALL).title("Open orders"))
+        .header_style(Style::default().fg(Color::Gray).modifier(Modifier::BOLD))
+        .widths(&widths)
+        .column_spacing(1);
+    f.render_widget(table, chunks[2]);
+
+    let events = account.history.as_ref().map_or(&[][..], |h| h.events());
+    let items = events.iter().map(|e| Text::raw(format!("{} {:>10} {:<10} {}",
+        e.date.format("%Y-%m-%d"), format!("{:.2}", e.amount), e.event_type, e.description())));
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Activity"));
+    f.render_widget(list, chunks[3]);
+}
+
+fn gain_text<'a>(gain: Option<f64>) -> Text<'a> {
+    match gain {
+        Some(g) if g > 0.0 => Text::styled(format!("{:+.2}", g), Style::default().fg(Color::Green)),
+        Some(g) if g < 0.0 => Text::styled(format!("{:+.2}", g), Style::default().fg(Color::Red)),
+        Some(g) => Text::raw(format!("{:.2}", g)),
+        None => Text::raw("-")
+    }
+}
+
+fn draw_ticket<B: Backend>(f: &mut Frame<B>, ticket: &OrderTicket, live: bool, area: Rect) {
+    let (environment, color) = if live { ("LIVE", Color::Red) } else { ("sandbox", Color::Green) };
+    let (title, height) = match &ticket.kind {
+        TicketKind::Equity => (format!("Order ${} ({})", ticket.symbol, environment), 19),
+        TicketKind::Option { option_symbol, side } => {
+            (format!("{} ${} ({})", side.as_str(), option_symbol, environment), 15)
+        },
+        TicketKind::Bracket { option_symbol, .. } => {
+            let symbol = option_symbol.as_ref().unwrap_or(&ticket.symbol);
+            (format!("Bracket ${} ({})", symbol, environment), 17)
+        },
+        TicketKind::Multileg(strategy) => {
+            let direction = if strategy.buy { "Long" } else { "Short" };
+            let title = format!("{} {} on ${} ({})", direction, strategy.kind.label().to_lowercase(), ticket.symbol, environment);
+            (title, 17 + strategy.legs.len() as u16)
+        }
+    };
+    let area = centered_rect(60, height, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+        .title(&title);
+
+    let mut text = vec![];
+    for field in ticket.fields() {
+        let (label, value) = match field {
+            TicketField::Side => ("Side", ticket.side.as_str().to_string()),
+            TicketField::Quantity => ("Quantity", ticket.quantity.clone()),
+            TicketField::Type => ("Type", ticket.order_type.as_str().to_string()),
+            TicketField::Price if matches!(ticket.kind, TicketKind::Multileg(_)) => ("Net limit", ticket.price.clone()),
+            TicketField::Price => ("Limit price", ticket.price.clone()),
+            TicketField::Stop => ("Stop price", ticket.stop.clone()),
+            TicketField::Duration => ("Duration", ticket.duration.as_str().to_string()),
+            TicketField::TakeProfit => ("Take profit", ticket.take_profit.clone()),
+            TicketField::StopLoss => ("Stop loss", ticket.stop_loss.clone())
+        };
+        let style = if field == ticket.focus {
+            Style::default().fg(Color::Yellow).modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let marker = if field == ticket.focus { "> " } else { "  " };
+        text.push(Text::styled(format!("{}{:<12} {}\n", marker, label, value), style));
+    }
+    text.push(Text::raw("\n"));
+    if let TicketKind::Multileg(strategy) = &ticket.kind {
+        for leg in &strategy.legs {
+            text.push(Text::raw(format!("{:<13} {}\n", leg.side.as_str(), leg.contract.symbol)));
+        }
+        let quantity = ticket.quantity.parse().unwrap_or(0.0);
+        text.extend(strategy_cost_text(strategy, quantity));
+        text.push(Text::raw("\n\n"));
+    } else if let Some(risk) = ticket.risk_to_stop() {
+        text.push(Text::styled(format!("Risk to stop: ${:.2}\n\n", risk), Style::default().modifier(Modifier::BOLD)));
+    }
+    match &ticket.stage {
+        TicketStage::Editing => {
+            text.push(Text::raw("Tab/Up/Down: field  Left/Right: change\nEnter: preview  Esc: cancel"));
+        },
+        TicketStage::Previewing => text.push(Text::raw("Previewing...")),
+        TicketStage::Previewed(preview) => {
+            text.push(Text::raw(format!("Estimated cost: ${:.2}\nCommission: ${:.2}  Fees: ${:.2}\n",
+                preview.order_cost.unwrap_or(preview.cost), preview.commission, preview.fees)));
+            let margin = preview.margin_change.map_or("-".to_string(), |m| format!("${:.2}", m));
+            let day_trades = preview.day_trades.map_or("-".to_string(), |d| d.to_string());
+            text.push(Text::raw(format!("Margin change: {}  Day trades: {}\n", margin, day_trades)));
+            text.push(Text::styled("Press y to place this order, n to edit", Style::default().fg(color).modifier(Modifier::BOLD)));
+        },
+        TicketStage::Submitting => text.push(Text::raw("Placing order...")),
+        TicketStage::Submitted(id) => {
+            text.push(Text::styled(format!("Order #{} placed.", id), Style::default().fg(Color::Green)));
+            text.push(Text::raw("\nEnter/Esc: close"));
+        },
+        TicketStage::Failed(msg) => text.push(Text::styled(msg.clone(), Style::default().fg(Color::Red)))
+    }
+    let paragraph = Paragraph::new(text.iter()).block(block).wrap(true);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// A `width` by `height` rectangle in the middle of `area`.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
+}
+
+fn draw_graph_message<B: Backend>(f: &mut Frame<B>, block: Block, area: Rect, message: String) {
+    let text = [Text::raw(message)];
+    let paragraph = Paragraph::new(text.iter()).block(block).wrap(false);
+    f.render_widget(paragraph, area);
 }
 
 fn draw_log_section<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
@@ -0,0 +1,244 @@
+use std::{env, fs, io};
+use std::path::{Path, PathBuf};
+
+use crate::api::AccountProvider;
+use crate::api::client::Result;
+use crate::data::symbology;
+use crate::data::watchlist::{self, Merge, SavedWatchlist, WatchlistsFile};
+
+const CONFIG_DIRECTORY: &str = "stonks_cli";
+const WATCHLISTS_FILENAME: &str = "watchlists.json";
+
+/// Where the watchlists file goes when no path is given:
+/// `$XDG_CONFIG_HOME/stonks_cli`, or `~/.config/stonks_cli`.
+pub fn default_store_path() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join(CONFIG_DIRECTORY).join(WATCHLISTS_FILENAME))
+}
+
+/// Read the watchlists file at `path`. One that doesn't exist yet holds just
+/// the default watchlist.
+pub fn load_watchlists(path: &Path) -> io::Result<WatchlistsFile> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(WatchlistsFile::default()),
+        Err(err) => return Err(err)
+    };
+    let mut file: WatchlistsFile = serde_json::from_str(&contents)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if file.watchlists.is_empty() {
+        file = WatchlistsFile::default();
+    }
+    for saved in &mut file.watchlists {
+        saved.symbols = saved.symbols.iter().map(|s| symbology::normalize(s)).collect();
+    }
+    Ok(file)
+}
+
+/// Write `file` to `path`, by way of a temporary file so a crash part way
+/// through can't leave it half written.
+pub fn save_watchlists(path: &Path, file: &WatchlistsFile) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(file)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, json)?;
+    fs::rename(&temporary, path)
+}
+
+/// WatchlistSync is the showing watchlist's symbols as edited here, and what
+/// they were when this side and Tradier last agreed. Watchlists that sync
+/// are kept in step with the Tradier watchlist of the same name.
+pub struct WatchlistSync {
+    pub name: String,
+    /// Whether the watchlist syncs with Tradier at all.
+    pub syncs: bool,
+    /// Tradier's id for the watchlist, once it's been found or created.
+    pub id: Option<String>,
+    pub tickers: Vec<String>,
+    pub base: Vec<String>,
+    pub syncing: bool,
+    /// Set when the list is edited during a sync, so another one follows,
+    /// and left set when a sync fails with edits Tradier hasn't seen.
+    pub pending: bool
+}
+
+impl WatchlistSync {
+    /// Start from a saved watchlist. One that's never been synced is taken
+    /// to be in step with Tradier until a sync says otherwise.
+    pub fn from_saved(saved: &SavedWatchlist) -> Self {
+        WatchlistSync {
+            name: saved.name.clone(),
+            syncs: saved.syncs(),
+            id: None,
+            base: saved.synced.clone().unwrap_or_else(|| saved.symbols.clone()),
+            tickers: saved.symbols.clone(),
+            syncing: false,
+            pending: false
+        }
+    }
+
+    pub fn add(&mut self, ticker: &str) -> bool {
+        if self.tickers.iter().any(|t| symbology::same_symbol(t, ticker)) {
+            return false;
+        }
+        self.tickers.push(symbology::normalize(ticker));
+        true
+    }
+
+    pub fn remove(&mut self, ticker: &str) -> bool {
+        let len = self.tickers.len();
+        self.tickers.retain(|t| !symbology::same_symbol(t, ticker));
+        self.tickers.len() != len
+    }
+}
+
+/// Pull the watchlist named `name`, merge in what changed on both sides since
+/// `base`, and push the result back if Tradier's copy differs. Only
+/// watchlists that sync get here, so one that doesn't exist yet on Tradier
+/// is created from `local`. Returns the watchlist's id and the merge, whose
+/// symbols are now on both sides.
+pub async fn sync(
+    accounts: &dyn AccountProvider,
+    name: String,
+    id: Option<String>,
+    base: &[String],
+    local: &[String]
+) -> Result<(String, Merge)> {
+    let id = match id {
+        Some(id) => id,
+        None => match accounts.get_watchlists().await?.find(&name) {
+            Some(summary) => summary.id.clone(),
+            None => {
+                let created = accounts.create_watchlist(name, local.to_vec()).await?;
+                let merge = Merge { symbols: created.symbols(), added_remotely: vec![], removed_remotely: vec![] };
+                return Ok((created.id().to_string(), merge));
+            }
+        }
+    };
+    let remote = accounts.get_watchlist(id.clone()).await?.symbols();
+    let mut merge = watchlist::merge(base, local, &remote);
+    if merge.symbols != remote {
+        // Keep what Tradier actually stored, in case it tidied the list.
+        merge.symbols = accounts.update_watchlist(id.clone(), name, merge.symbols).await?.symbols();
+    }
+    Ok((id, merge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory for one test, removed when it's dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("stonks_cli-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn default_symbols() -> Vec<String> {
+        WatchlistsFile::default().watchlists.remove(0).symbols
+    }
+
+    #[test]
+    fn a_missing_file_holds_the_default_watchlist() {
+        let dir = TempDir::new("missing");
+        let file = load_watchlists(&dir.0.join(WATCHLISTS_FILENAME)).unwrap();
+        assert_eq!(file.active, "default");
+        assert_eq!(file.active().symbols, default_symbols());
+    }
+
+    #[test]
+    fn an_empty_list_falls_back_to_the_default() {
+        let dir = TempDir::new("empty");
+        let path = dir.0.join(WATCHLISTS_FILENAME);
+        fs::write(&path, r#"{ "active": "tech", "watchlists": [] }"#).unwrap();
+        let file = load_watchlists(&path).unwrap();
+        assert_eq!(file.watchlists.len(), 1);
+        assert_eq!(file.active().symbols, default_symbols());
+    }
+
+    #[test]
+    fn loaded_symbols_are_normalized() {
+        let dir = TempDir::new("normalized");
+        let path = dir.0.join(WATCHLISTS_FILENAME);
+        fs::write(&path, r#"{ "active": "default", "watchlists": [
+            { "name": "default", "symbols": ["spy", "BRK/B", "ABR-D"] }
+        ] }"#).unwrap();
+        assert_eq!(load_watchlists(&path).unwrap().active().symbols, vec!["SPY", "BRK.B", "ABRpD"]);
+    }
+
+    #[test]
+    fn a_malformed_file_is_reported() {
+        let dir = TempDir::new("malformed");
+        let path = dir.0.join(WATCHLISTS_FILENAME);
+        fs::write(&path, r#"{ "watchlists": "#).unwrap();
+        assert_eq!(load_watchlists(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn saved_watchlists_load_back() {
+        let dir = TempDir::new("saved");
+        let path = dir.0.join(CONFIG_DIRECTORY).join(WATCHLISTS_FILENAME);
+        let mut file = WatchlistsFile::default();
+        file.watchlists.push(SavedWatchlist {
+            name: "tech".to_string(),
+            symbols: vec!["AAPL".to_string(), "MSFT".to_string()],
+            sync: true,
+            synced: Some(vec!["AAPL".to_string()])
+        });
+        file.active = "tech".to_string();
+        save_watchlists(&path, &file).unwrap();
+        // The temporary file is renamed over the real one, not left behind.
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let loaded = load_watchlists(&path).unwrap();
+        assert_eq!(loaded.active, "tech");
+        let tech = loaded.active();
+        assert_eq!(tech.symbols, vec!["AAPL", "MSFT"]);
+        assert!(tech.sync);
+        assert_eq!(tech.synced, Some(vec!["AAPL".to_string()]));
+
+        file.watchlists.pop();
+        save_watchlists(&path, &file).unwrap();
+        assert_eq!(load_watchlists(&path).unwrap().watchlists.len(), 1);
+    }
+
+    // One test, so nothing else reads XDG_CONFIG_HOME or HOME while they're
+    // changed.
+    #[test]
+    fn the_store_follows_xdg_config_home_then_home() {
+        let (xdg, home) = (env::var_os("XDG_CONFIG_HOME"), env::var_os("HOME"));
+        env::set_var("XDG_CONFIG_HOME", "/xdg/config");
+        env::set_var("HOME", "/home/trader");
+        assert_eq!(default_store_path(), Some(PathBuf::from("/xdg/config/stonks_cli/watchlists.json")));
+        env::set_var("XDG_CONFIG_HOME", "relative/config");
+        assert_eq!(default_store_path(), Some(PathBuf::from("/home/trader/.config/stonks_cli/watchlists.json")));
+        env::remove_var("XDG_CONFIG_HOME");
+        assert_eq!(default_store_path(), Some(PathBuf::from("/home/trader/.config/stonks_cli/watchlists.json")));
+        env::remove_var("HOME");
+        assert_eq!(default_store_path(), None);
+        for (key, value) in &[("XDG_CONFIG_HOME", xdg), ("HOME", home)] {
+            match value {
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key)
+            }
+        }
+    }
+}
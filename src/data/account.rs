@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::data::de;
+
+// API Representation
+//
+// Account endpoints answer an empty list with the string "null" rather than
+// `null`, which `de::null_or` treats the same.
+
+#[derive(Debug, Deserialize)]
+pub struct ProfileDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    profile: Option<Profile>
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Profile {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    pub account: Vec<Account>
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Account {
+    pub account_number: String,
+    #[serde(rename(deserialize = "type"))]
+    pub account_type: AccountType,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub classification: String,
+    pub option_level: Option<u8>,
+    #[serde(default)]
+    pub day_trader: bool
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountType {
+    Cash,
+    Margin,
+    Pdt
+}
+
+impl AccountType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AccountType::Cash => "cash",
+            AccountType::Margin => "margin",
+            AccountType::Pdt => "day trading"
+        }
+    }
+}
+
+impl ProfileDataModel {
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+}
+
+impl Profile {
+    pub fn find_account(&self, account_number: &str) -> Option<&Account> {
+        self.account.iter().find(|a| a.account_number == account_number)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BalancesDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    balances: Option<Balances>
+}
+
+/// Balances are an account's cash and equity. Which of `margin`, `cash` and
+/// `pdt` is present depends on the account type.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Balances {
+    #[serde(default)]
+    pub account_number: String,
+    pub account_type: Option<AccountType>,
+    #[serde(default)]
+    pub total_equity: f64,
+    #[serde(default)]
+    pub total_cash: f64,
+    #[serde(default)]
+    pub market_value: f64,
+    pub open_pl: Option<f64>,
+    pub close_pl: Option<f64>,
+    pub pending_orders_count: Option<u32>,
+    #[serde(default, deserialize_with = "de::null_or")]
+    pub margin: Option<MarginBalances>,
+    #[serde(default, deserialize_with = "de::null_or")]
+    pub cash: Option<CashBalances>,
+    #[serde(default, deserialize_with = "de::null_or")]
+    pub pdt: Option<PdtBalances>
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct MarginBalances {
+    pub stock_buying_power: f64,
+    pub option_buying_power: Option<f64>
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct CashBalances {
+    pub cash_available: f64,
+    pub unsettled_funds: Option<f64>
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct PdtBalances {
+    pub stock_buying_power: f64,
+    pub option_buying_power: Option<f64>,
+    pub day_trade_buying_power: Option<f64>
+}
+
+impl BalancesDataModel {
+    pub fn balances(&self) -> Option<&Balances> {
+        self.balances.as_ref()
+    }
+}
+
+impl Balances {
+    /// Stock buying power for margin and day-trading accounts; settled cash
+    /// for cash accounts.
+    pub fn buying_power(&self) -> Option<f64> {
+        self.margin.map(|m| m.stock_buying_power)
+            .or_else(|| self.pdt.map(|p| p.stock_buying_power))
+            .or_else(|| self.cash.map(|c| c.cash_available))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PositionsDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    positions: Option<Positions>
+}
+
+#[derive(Debug, Deserialize)]
+struct Positions {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    position: Vec<Position>
+}
+
+/// Position is a holding. `cost_basis` is the total paid, not per share, and
+/// short positions have a negative quantity.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Position {
+    pub id: Option<u64>,
+    pub symbol: String,
+    pub quantity: f64,
+    pub cost_basis: f64,
+    pub date_acquired: Option<DateTime<Utc>>
+}
+
+impl PositionsDataModel {
+    pub fn positions(&self) -> &[Position] {
+        self.positions.as_ref().map_or(&[], |p| &p.position[..])
+    }
+}
+
+impl Position {
+    /// Shares per unit: 100 for an option contract (an OCC symbol such as
+    /// `AAPL200717C00360000`), otherwise 1.
+    pub fn multiplier(&self) -> f64 {
+        if self.is_option() { 100.0 } else { 1.0 }
+    }
+
+    pub fn is_option(&self) -> bool {
+        is_option_symbol(&self.symbol)
+    }
+
+    /// The stock an option position is on, or the symbol itself.
+    pub fn underlying(&self) -> &str {
+        if self.is_option() { &self.symbol[..self.symbol.len() - 15] } else { &self.symbol }
+    }
+
+    pub fn market_value(&self, last: f64) -> f64 {
+        self.quantity * last * self.multiplier()
+    }
+
+    /// Unrealized profit or loss at `last`.
+    pub fn gain(&self, last: f64) -> f64 {
+        self.market_value(last) - self.cost_basis
+    }
+}
+
+fn is_option_symbol(symbol: &str) -> bool {
+    if symbol.len() <= 15 || !symbol.is_ascii() {
+        return false;
+    }
+    let suffix = &symbol[symbol.len() - 15..];
+    suffix[..6].chars().all(|c| c.is_ascii_digit())
+        && (&suffix[6..7] == "C" || &suffix[6..7] == "P")
+        && suffix[7..].chars().all(|c| c.is_ascii_digit())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrdersDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    orders: Option<Orders>
+}
+
+#[derive(Debug, Deserialize)]
+struct Orders {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    order: Vec<Order>
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Order {
+    pub id: Option<u64>,
+    #[serde(rename(deserialize = "type"))]
+    pub order_type: String,
+    pub symbol: String,
+    pub option_symbol: Option<String>,
+    pub side: String,
+    pub quantity: f64,
+    pub status: OrderStatus,
+    pub duration: String,
+    pub price: Option<f64>,
+    pub stop_price: Option<f64>,
+    pub avg_fill_price: Option<f64>,
+    pub exec_quantity: Option<f64>,
+    pub remaining_quantity: Option<f64>,
+    pub create_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub class: String
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Open,
+    PartiallyFilled,
+    Filled,
+    Expired,
+    Canceled,
+    Pending,
+    Rejected,
+    Error,
+    #[serde(other)]
+    Other
+}
+
+impl OrdersDataModel {
+    pub fn orders(&self) -> &[Order] {
+        self.orders.as_ref().map_or(&[], |o| &o.order[..])
+    }
+
+    /// Orders that can still fill.
+    pub fn open_orders(&self) -> Vec<&Order> {
+        self.orders().iter()
+            .filter(|o| matches!(o.status, OrderStatus::Open | OrderStatus::PartiallyFilled | OrderStatus::Pending))
+            .collect()
+    }
+}
+
+impl Order {
+    /// The contract for option orders, the stock otherwise.
+    pub fn display_symbol(&self) -> &str {
+        self.option_symbol.as_deref().unwrap_or(&self.symbol)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountHistoryDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    history: Option<AccountHistory>
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountHistory {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    event: Vec<HistoryEvent>
+}
+
+/// HistoryEvent is one entry in an account's activity: a trade, a dividend,
+/// a transfer and so on. The details are in an object named after the type.
+#[derive(Debug, Deserialize, Clone)]
+pub struct HistoryEvent {
+    pub amount: f64,
+    pub date: DateTime<Utc>,
+    #[serde(rename(deserialize = "type"))]
+    pub event_type: String,
+    #[serde(flatten)]
+    details: HashMap<String, Value>
+}
+
+impl AccountHistoryDataModel {
+    pub fn events(&self) -> &[HistoryEvent] {
+        self.history.as_ref().map_or(&[], |h| &h.event[..])
+    }
+}
+
+impl HistoryEvent {
+    pub fn description(&self) -> &str {
+        self.details.get(&self.event_type)
+            .and_then(|d| d["description"].as_str())
+            .unwrap_or("")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrderPreviewDataModel {
+    order: OrderPreview
+}
+
+/// OrderPreview is Tradier's estimate for an order that hasn't been placed.
+#[derive(Debug, Deserialize, Clone)]
+pub struct OrderPreview {
+    pub status: String,
+    #[serde(default)]
+    pub result: bool,
+    #[serde(default)]
+    pub cost: f64,
+    #[serde(default)]
+    pub commission: f64,
+    #[serde(default)]
+    pub fees: f64,
+    pub order_cost: Option<f64>,
+    pub margin_change: Option<f64>,
+    pub day_trades: Option<u32>
+}
+
+impl OrderPreviewDataModel {
+    pub fn preview(&self) -> &OrderPreview {
+        &self.order
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrderConfirmationDataModel {
+    order: OrderConfirmation
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OrderConfirmation {
+    pub id: u64,
+    pub status: String
+}
+
+impl OrderConfirmationDataModel {
+    pub fn confirmation(&self) -> &OrderConfirmation {
+        &self.order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<'a, T: Deserialize<'a>>(json: &'a str) -> T {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn profile_with_one_account() {
+        let profile: ProfileDataModel = parse(include_str!("../../fixtures/user/profile.json"));
+        let profile = profile.profile().unwrap();
+        assert_eq!(profile.name, "Sandbox Trader");
+        assert_eq!(profile.id, "id-sandbox");
+        let account = &profile.account[0];
+        assert_eq!((account.status.as_str(), account.classification.as_str()), ("active", "individual"));
+        assert_eq!((account.option_level, account.day_trader), (Some(2), false));
+        assert_eq!(profile.account.len(), 1);
+        assert_eq!(profile.find_account("VA00000001").unwrap().account_type, AccountType::Margin);
+        assert!(profile.find_account("VA00000002").is_none());
+    }
+
+    #[test]
+    fn profile_with_several_accounts() {
+        let profile: ProfileDataModel = parse(r#"{"profile": {"id": "id-1", "name": "Trader", "account": [
+            {"account_number": "VA1", "type": "cash"},
+            {"account_number": "VA2", "type": "pdt"}
+        ]}}"#);
+        let types: Vec<AccountType> = profile.profile().unwrap().account.iter().map(|a| a.account_type).collect();
+        assert_eq!(types, vec![AccountType::Cash, AccountType::Pdt]);
+    }
+
+    #[test]
+    fn margin_balances() {
+        let balances: BalancesDataModel = parse(include_str!("../../fixtures/accounts/balances.json"));
+        let balances = balances.balances().unwrap();
+        assert_eq!(balances.total_equity, 17642.18);
+        assert_eq!(balances.market_value, 10903.6);
+        assert_eq!(balances.buying_power(), Some(13477.16));
+        assert_eq!(balances.account_number, "VA00000001");
+        assert_eq!(balances.account_type, Some(AccountType::Margin));
+        assert_eq!(balances.pending_orders_count, Some(2));
+        assert_eq!(balances.margin.unwrap().option_buying_power, Some(6738.58));
+    }
+
+    #[test]
+    fn cash_balances() {
+        let balances: BalancesDataModel = parse(r#"{"balances": {"account_type": "cash", "total_equity": 100.0,
+            "cash": {"cash_available": 75.5, "unsettled_funds": 24.5}, "margin": null}}"#);
+        let cash = balances.balances().unwrap();
+        assert_eq!(cash.buying_power(), Some(75.5));
+        assert_eq!(cash.cash.unwrap().unsettled_funds, Some(24.5));
+        assert_eq!(cash.pending_orders_count, None);
+        let balances: BalancesDataModel = parse(r#"{"balances": "null"}"#);
+        assert!(balances.balances().is_none());
+    }
+
+    #[test]
+    fn positions() {
+        let positions: PositionsDataModel = parse(include_str!("../../fixtures/accounts/positions.json"));
+        let positions = positions.positions();
+        assert_eq!(positions.len(), 4);
+        let option = &positions[3];
+        assert!(option.is_option());
+        assert_eq!(option.underlying(), "SPY");
+        assert_eq!(option.market_value(6.5), 1300.0);
+        assert_eq!(option.gain(6.5), 60.0);
+        assert!(!positions[0].is_option());
+    }
+
+    #[test]
+    fn positions_one_or_none() {
+        let positions: PositionsDataModel = parse(r#"{"positions": {"position": {"cost_basis": 100.0,
+            "date_acquired": "2020-03-18T14:31:02.114Z", "id": 1, "quantity": 1.0, "symbol": "AAPL"}}}"#);
+        assert_eq!(positions.positions().len(), 1);
+        assert_eq!(positions.positions()[0].id, Some(1));
+        assert_eq!(positions.positions()[0].date_acquired.unwrap().to_rfc3339(), "2020-03-18T14:31:02.114+00:00");
+        let positions: PositionsDataModel = parse(r#"{"positions": "null"}"#);
+        assert!(positions.positions().is_empty());
+        let positions: PositionsDataModel = parse(r#"{"positions": null}"#);
+        assert!(positions.positions().is_empty());
+    }
+
+    #[test]
+    fn orders() {
+        let orders: OrdersDataModel = parse(include_str!("../../fixtures/accounts/orders.json"));
+        assert_eq!(orders.orders().len(), 4);
+        let open: Vec<&str> = orders.open_orders().iter().map(|o| o.display_symbol()).collect();
+        assert_eq!(open, vec!["AMD", "SPY200717C00310000"]);
+        let partial = &orders.orders()[1];
+        assert_eq!((partial.id, partial.class.as_str()), (Some(228176), "option"));
+        assert_eq!((partial.avg_fill_price, partial.remaining_quantity), (Some(7.5), Some(1.0)));
+        assert_eq!(partial.create_date.unwrap().to_rfc3339(), "2020-07-02T14:10:44.602+00:00");
+        let orders: OrdersDataModel = parse(r#"{"orders": "null"}"#);
+        assert!(orders.orders().is_empty());
+    }
+
+    #[test]
+    fn unknown_order_statuses_are_other() {
+        let orders: OrdersDataModel = parse(r#"{"orders": {"order": {"type": "limit", "symbol": "AMD",
+            "side": "buy", "quantity": 1.0, "status": "held", "duration": "day"}}}"#);
+        assert_eq!(orders.orders()[0].status, OrderStatus::Other);
+        assert!(orders.orders()[0].id.is_none() && orders.orders()[0].create_date.is_none());
+    }
+
+    #[test]
+    fn history() {
+        let history: AccountHistoryDataModel = parse(include_str!("../../fixtures/accounts/history.json"));
+        let events = history.events();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].description(), "Bought To Open 2 SPY Jul 17 2020 $310 Call");
+        assert_eq!(events[1].event_type, "dividend");
+        let history: AccountHistoryDataModel = parse(r#"{"history": "null"}"#);
+        assert!(history.events().is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use serde::Deserialize;
+
+use crate::data::de;
+use crate::util::calendar::{self as rules, TradingCalendar};
+
+// API Representation
+
+#[derive(Debug, Deserialize, Default)]
+pub struct CalendarDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    calendar: Option<CalendarMonth>
+}
+
+#[derive(Debug, Deserialize)]
+struct CalendarMonth {
+    month: u32,
+    year: i32,
+    #[serde(default, deserialize_with = "de::null_or")]
+    days: Option<CalendarDays>
+}
+
+#[derive(Debug, Deserialize)]
+struct CalendarDays {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    day: Vec<CalendarDay>
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DayStatus {
+    Open,
+    Closed
+}
+
+/// CalendarDay is one day of the exchange calendar. Closed days have no
+/// sessions; early-close days have a shorter `open` session.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub status: DayStatus,
+    #[serde(default)]
+    pub description: String,
+    pub premarket: Option<Session>,
+    pub open: Option<Session>,
+    pub postmarket: Option<Session>
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Session {
+    #[serde(deserialize_with = "de::hour_minute")]
+    pub start: NaiveTime,
+    #[serde(deserialize_with = "de::hour_minute")]
+    pub end: NaiveTime
+}
+
+impl CalendarDataModel {
+    /// The (year, month) this calendar covers and its days.
+    pub fn into_month(self) -> Option<((i32, u32), Vec<CalendarDay>)> {
+        self.calendar.map(|c| {
+            let days = c.days.map_or(vec![], |d| d.day);
+            ((c.year, c.month), days)
+        })
+    }
+}
+
+// Lookup
+
+pub const REGULAR_OPEN: (u32, u32) = (9, 30);
+pub const REGULAR_CLOSE: (u32, u32) = (16, 0);
+pub const PREMARKET_OPEN: (u32, u32) = (4, 0);
+pub const POSTMARKET_CLOSE: (u32, u32) = (20, 0);
+
+/// MarketCalendar caches exchange calendars a month at a time. For months
+/// that haven't been loaded it falls back to the NYSE holiday rules.
+#[derive(Debug, Clone, Default)]
+pub struct MarketCalendar {
+    months: HashMap<(i32, u32), HashMap<NaiveDate, CalendarDay>>
+}
+
+impl MarketCalendar {
+    pub fn new() -> Self {
+        MarketCalendar::default()
+    }
+
+    pub fn has_month(&self, year: i32, month: u32) -> bool {
+        self.months.contains_key(&(year, month))
+    }
+
+    pub fn insert(&mut self, calendar: CalendarDataModel) {
+        if let Some((key, days)) = calendar.into_month() {
+            let days = days.into_iter().map(|d| (d.date, d)).collect();
+            self.months.insert(key, days);
+        }
+    }
+
+    pub fn day(&self, date: NaiveDate) -> Option<&CalendarDay> {
+        self.months.get(&(date.year(), date.month()))?.get(&date)
+    }
+
+    /// The regular session, shortened on early-close days. `None` when the
+    /// market is closed.
+    pub fn regular_session(&self, date: NaiveDate) -> Option<Session> {
+        if !self.is_trading_day(date) {
+            return None;
+        }
+        let close = rules::early_close(date).unwrap_or_else(|| time(REGULAR_CLOSE));
+        let default = Session { start: time(REGULAR_OPEN), end: close };
+        Some(self.day(date).and_then(|d| d.open).unwrap_or(default))
+    }
+
+    /// Pre-market open through post-market close. `None` when the market is
+    /// closed.
+    pub fn extended_session(&self, date: NaiveDate) -> Option<Session> {
+        let regular = self.regular_session(date)?;
+        let day = self.day(date);
+        Some(Session {
+            start: day.and_then(|d| d.premarket).map_or(time(PREMARKET_OPEN), |s| s.start.min(regular.start)),
+            end: day.and_then(|d| d.postmarket).map_or(time(POSTMARKET_CLOSE), |s| s.end.max(regular.end))
+        })
+    }
+}
+
+impl TradingCalendar for MarketCalendar {
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        match self.day(date) {
+            Some(day) => day.status == DayStatus::Open,
+            None => rules::is_trading_day(date)
+        }
+    }
+}
+
+fn time((hour, minute): (u32, u32)) -> NaiveTime {
+    NaiveTime::from_hms(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // December 2018, with the market closed for a national day of mourning
+    // the holiday rules know nothing about.
+    const DECEMBER_2018: &str = r#"{"calendar": {"month": 12, "year": 2018, "days": {"day": [
+        {"date": "2018-12-04", "status": "open", "open": {"start": "09:30", "end": "16:00"}},
+        {"date": "2018-12-05", "status": "closed", "description": "Market is closed for the National Day of Mourning"},
+        {"date": "2018-12-06", "status": "open", "open": {"start": "09:30", "end": "16:00"}},
+        {"date": "2018-12-24", "status": "open", "open": {"start": "09:30", "end": "13:00"}}
+    ]}}}"#;
+
+    fn december() -> MarketCalendar {
+        let mut calendar = MarketCalendar::new();
+        calendar.insert(serde_json::from_str(DECEMBER_2018).unwrap());
+        calendar
+    }
+
+    #[test]
+    fn loaded_days_override_the_rules() {
+        let calendar = december();
+        let closed = NaiveDate::from_ymd(2018, 12, 5);
+        assert!(rules::is_trading_day(closed));
+        assert!(!calendar.is_trading_day(closed));
+        assert_eq!(calendar.next_trading_day(NaiveDate::from_ymd(2018, 12, 4)), NaiveDate::from_ymd(2018, 12, 6));
+        assert_eq!(calendar.last_trading_day(closed), NaiveDate::from_ymd(2018, 12, 4));
+        assert_eq!(calendar.regular_session(closed), None);
+    }
+
+    #[test]
+    fn unloaded_months_fall_back_to_the_rules() {
+        let calendar = december();
+        assert!(!calendar.has_month(2019, 1));
+        assert_eq!(calendar.next_trading_day(NaiveDate::from_ymd(2018, 12, 31)), NaiveDate::from_ymd(2019, 1, 2));
+        let early = calendar.regular_session(NaiveDate::from_ymd(2019, 7, 3)).unwrap();
+        assert_eq!(early.end, NaiveTime::from_hms(13, 0, 0));
+    }
+}
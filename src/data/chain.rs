@@ -0,0 +1,183 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::data::de;
+use crate::data::options::OptionType;
+
+// API Representation
+
+// Each of these is null when the symbol has no listed options.
+#[derive(Debug, Deserialize)]
+pub struct ExpirationsDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    expirations: Option<Expirations>
+}
+
+#[derive(Debug, Deserialize)]
+struct Expirations {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    date: Vec<NaiveDate>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StrikesDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    strikes: Option<Strikes>
+}
+
+#[derive(Debug, Deserialize)]
+struct Strikes {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    strike: Vec<f64>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OptionChainDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    options: Option<OptionContracts>
+}
+
+#[derive(Debug, Deserialize)]
+struct OptionContracts {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    option: Vec<OptionContract>
+}
+
+/// OptionContract is one call or put in a chain. Prices are null for
+/// contracts that haven't traded.
+#[derive(Debug, Deserialize, Clone)]
+pub struct OptionContract {
+    pub symbol: String,
+    #[serde(default)]
+    pub description: String,
+    pub underlying: String,
+    pub strike: f64,
+    pub expiration_date: NaiveDate,
+    pub option_type: OptionType,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    #[serde(rename(deserialize = "bidsize"))]
+    pub bid_size: Option<u32>,
+    #[serde(rename(deserialize = "asksize"))]
+    pub ask_size: Option<u32>,
+    pub last: Option<f64>,
+    #[serde(rename(deserialize = "change"))]
+    pub change_points: Option<f64>,
+    pub volume: Option<u64>,
+    pub open_interest: Option<u64>,
+    pub contract_size: Option<u32>,
+    #[serde(default, deserialize_with = "de::null_or")]
+    pub greeks: Option<Greeks>
+}
+
+/// Greeks and implied volatility, as computed by Tradier's data vendor.
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct Greeks {
+    pub delta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub theta: Option<f64>,
+    pub vega: Option<f64>,
+    pub rho: Option<f64>,
+    pub phi: Option<f64>,
+    pub bid_iv: Option<f64>,
+    pub mid_iv: Option<f64>,
+    pub ask_iv: Option<f64>,
+    pub smv_vol: Option<f64>
+}
+
+impl ExpirationsDataModel {
+    pub fn dates(&self) -> &[NaiveDate] {
+        self.expirations.as_ref().map_or(&[], |e| &e.date[..])
+    }
+}
+
+impl StrikesDataModel {
+    pub fn strikes(&self) -> &[f64] {
+        self.strikes.as_ref().map_or(&[], |s| &s.strike[..])
+    }
+}
+
+impl OptionChainDataModel {
+    pub fn contracts(&self) -> &[OptionContract] {
+        self.options.as_ref().map_or(&[], |o| &o.option[..])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contracts().is_empty()
+    }
+
+    /// Every strike with its call and put, lowest strike first.
+    pub fn by_strike(&self) -> Vec<(f64, Option<&OptionContract>, Option<&OptionContract>)> {
+        let mut strikes: Vec<f64> = self.contracts().iter().map(|c| c.strike).collect();
+        strikes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        strikes.dedup();
+        strikes.into_iter().map(|strike| {
+            let find = |option_type| self.contracts().iter()
+                .find(|c| c.strike == strike && c.option_type == option_type);
+            (strike, find(OptionType::Call), find(OptionType::Put))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(json: &str) -> OptionChainDataModel {
+        serde_json::from_str(json).unwrap()
+    }
+
+    const CALL: &str = r#"{"symbol":"SPY200706C00310000","underlying":"SPY","strike":310.0,"expiration_date":"2020-07-06","option_type":"call","bid":3.1,"ask":3.2,"bidsize":5,"asksize":7,"last":null,"change":null,"volume":0,"open_interest":120,"contract_size":100,"greeks":null}"#;
+
+    #[test]
+    fn the_recorded_chain_reads() {
+        let chain = chain(include_str!("../../fixtures/markets/options/chains.json"));
+        assert_eq!(chain.contracts().len(), 22);
+        assert!(chain.contracts()[0].greeks.is_some());
+        assert!(chain.contracts()[1].greeks.is_none());
+    }
+
+    #[test]
+    fn a_single_contract_is_an_object() {
+        let chain = chain(&format!(r#"{{"options":{{"option":{}}}}}"#, CALL));
+        assert_eq!(chain.contracts().len(), 1);
+        let call = &chain.contracts()[0];
+        assert_eq!(call.option_type, OptionType::Call);
+        assert_eq!((call.bid, call.last, call.bid_size), (Some(3.1), None, Some(5)));
+        assert!(call.greeks.is_none());
+        assert_eq!(call.description, "");
+    }
+
+    #[test]
+    fn no_options_are_null() {
+        assert!(chain(r#"{"options":null}"#).is_empty());
+        assert!(chain(r#"{"options":"null"}"#).is_empty());
+        assert!(chain(r#"{}"#).by_strike().is_empty());
+    }
+
+    #[test]
+    fn contracts_pair_up_by_strike_lowest_first() {
+        let put = CALL.replace("C00310000", "P00310000").replace("\"call\"", "\"put\"");
+        let lower = CALL.replace("C00310000", "C00305000").replace("310.0", "305.0");
+        let chain = chain(&format!(r#"{{"options":{{"option":[{},{},{}]}}}}"#, put, CALL, lower));
+        let rows = chain.by_strike();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, 305.0);
+        assert_eq!(rows[0].1.map(|c| c.symbol.as_str()), Some("SPY200706C00305000"));
+        assert!(rows[0].2.is_none());
+        assert_eq!(rows[1].0, 310.0);
+        assert_eq!(rows[1].1.map(|c| c.symbol.as_str()), Some("SPY200706C00310000"));
+        assert_eq!(rows[1].2.map(|c| c.symbol.as_str()), Some("SPY200706P00310000"));
+    }
+
+    #[test]
+    fn strikes_read_one_or_many() {
+        let strikes: StrikesDataModel = serde_json::from_str(include_str!("../../fixtures/markets/options/strikes.json")).unwrap();
+        assert_eq!(strikes.strikes().len(), 11);
+        assert_eq!(strikes.strikes()[0], 300.0);
+        let one: StrikesDataModel = serde_json::from_str(r#"{"strikes":{"strike":310.0}}"#).unwrap();
+        assert_eq!(one.strikes(), &[310.0]);
+        let none: StrikesDataModel = serde_json::from_str(r#"{"strikes":null}"#).unwrap();
+        assert!(none.strikes().is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::data::de;
+use crate::data::calendar::MarketCalendar;
+use crate::util::calendar::TradingCalendar;
+use crate::util;
+
+// API Representation
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ClockDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    clock: Option<ClockModel>
+}
+
+#[derive(Debug, Deserialize)]
+struct ClockModel {
+    date: NaiveDate,
+    state: MarketState,
+    timestamp: i64,
+    // Only a time of day; the change may be on a later date.
+    #[serde(deserialize_with = "de::hour_minute")]
+    next_change: NaiveTime,
+    next_state: MarketState
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketState {
+    Premarket,
+    Open,
+    Postmarket,
+    Closed
+}
+
+impl MarketState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MarketState::Premarket => "Pre-market",
+            MarketState::Open => "Open",
+            MarketState::Postmarket => "After hours",
+            MarketState::Closed => "Closed"
+        }
+    }
+}
+
+impl ClockDataModel {
+    /// The clock with its next change pinned to a date: the clock's own date
+    /// if that time is still ahead and the market trades that day, otherwise
+    /// the next trading day.
+    pub fn into_clock(self, calendar: &MarketCalendar) -> Option<MarketClock> {
+        let clock = self.clock?;
+        let now = util::eastern().timestamp(clock.timestamp, 0);
+        let date = if clock.next_change > now.time() && calendar.is_trading_day(clock.date) {
+            clock.date
+        } else {
+            calendar.next_trading_day(clock.date)
+        };
+        Some(MarketClock {
+            state: clock.state,
+            next_state: clock.next_state,
+            next_change: exchange_time(date, clock.next_change)
+        })
+    }
+}
+
+// Clock
+
+/// MarketClock is the exchange's session state and when it next changes.
+#[derive(Debug, Clone)]
+pub struct MarketClock {
+    pub state: MarketState,
+    pub next_state: MarketState,
+    pub next_change: DateTime<Tz>
+}
+
+impl MarketClock {
+    /// Work the clock out from the trading calendar, for when the API can't
+    /// be asked.
+    pub fn from_calendar(calendar: &MarketCalendar, now: DateTime<Tz>) -> MarketClock {
+        let today = now.date().naive_local();
+        let sessions = calendar.extended_session(today).zip(calendar.regular_session(today));
+        if let Some((extended, regular)) = sessions {
+            let changes = [
+                (extended.start, MarketState::Premarket),
+                (regular.start, MarketState::Open),
+                (regular.end, MarketState::Postmarket),
+                (extended.end, MarketState::Closed)
+            ];
+            let mut state = MarketState::Closed;
+            for &(time, next_state) in changes.iter() {
+                if now.time() < time {
+                    return MarketClock { state, next_state, next_change: exchange_time(today, time) };
+                }
+                state = next_state;
+            }
+        }
+        let next_day = calendar.next_trading_day(today);
+        let start = calendar.extended_session(next_day).map(|s| s.start).unwrap_or(NaiveTime::from_hms(0, 0, 0));
+        MarketClock {
+            state: MarketState::Closed,
+            next_state: MarketState::Premarket,
+            next_change: exchange_time(next_day, start)
+        }
+    }
+
+    /// Whether the next change has already happened.
+    pub fn is_stale(&self, now: DateTime<Tz>) -> bool {
+        now >= self.next_change
+    }
+}
+
+// Sessions never start or end in the hour skipped when clocks go forward.
+fn exchange_time(date: NaiveDate, time: NaiveTime) -> DateTime<Tz> {
+    util::eastern_datetime(date, time).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        exchange_time(NaiveDate::from_ymd(year, month, day), NaiveTime::from_hms(hour, minute, 0))
+    }
+
+    fn clock(date: &str, state: &str, now: DateTime<Tz>, next_change: &str, next_state: &str) -> ClockDataModel {
+        serde_json::from_str(&format!(
+            r#"{{"clock":{{"date":"{}","description":"","state":"{}","timestamp":{},"next_change":"{}","next_state":"{}"}}}}"#,
+            date, state, now.timestamp(), next_change, next_state
+        )).unwrap()
+    }
+
+    fn worked_out(now: DateTime<Tz>) -> (MarketState, MarketState, DateTime<Tz>) {
+        let clock = MarketClock::from_calendar(&MarketCalendar::new(), now);
+        (clock.state, clock.next_state, clock.next_change)
+    }
+
+    #[test]
+    fn api_changes_later_today_stay_today() {
+        let now = at(2020, 7, 2, 12, 0);
+        let clock = clock("2020-07-02", "open", now, "16:00", "postmarket").into_clock(&MarketCalendar::new()).unwrap();
+        assert_eq!((clock.state, clock.next_state), (MarketState::Open, MarketState::Postmarket));
+        assert_eq!(clock.next_change, at(2020, 7, 2, 16, 0));
+        assert!(!clock.is_stale(now));
+        assert!(clock.is_stale(at(2020, 7, 2, 16, 0)));
+    }
+
+    #[test]
+    fn api_changes_after_the_close_roll_to_the_next_trading_day() {
+        // Friday 3 July 2020 is the observed Independence Day.
+        let calendar = MarketCalendar::new();
+        let evening = clock("2020-07-02", "closed", at(2020, 7, 2, 21, 0), "04:00", "premarket").into_clock(&calendar).unwrap();
+        assert_eq!(evening.next_change, at(2020, 7, 6, 4, 0));
+        let weekend = clock("2020-07-04", "closed", at(2020, 7, 4, 2, 0), "04:00", "premarket").into_clock(&calendar).unwrap();
+        assert_eq!(weekend.next_change, at(2020, 7, 6, 4, 0));
+    }
+
+    #[test]
+    fn a_missing_api_clock_is_none() {
+        assert!(ClockDataModel::default().into_clock(&MarketCalendar::new()).is_none());
+        let null: ClockDataModel = serde_json::from_str(r#"{"clock":null}"#).unwrap();
+        assert!(null.into_clock(&MarketCalendar::new()).is_none());
+    }
+
+    #[test]
+    fn the_day_moves_through_every_session() {
+        use MarketState::*;
+        assert_eq!(worked_out(at(2020, 7, 2, 3, 0)), (Closed, Premarket, at(2020, 7, 2, 4, 0)));
+        assert_eq!(worked_out(at(2020, 7, 2, 5, 0)), (Premarket, Open, at(2020, 7, 2, 9, 30)));
+        assert_eq!(worked_out(at(2020, 7, 2, 9, 30)), (Open, Postmarket, at(2020, 7, 2, 16, 0)));
+        assert_eq!(worked_out(at(2020, 7, 2, 17, 0)), (Postmarket, Closed, at(2020, 7, 2, 20, 0)));
+        assert_eq!(worked_out(at(2020, 7, 2, 21, 0)), (Closed, Premarket, at(2020, 7, 6, 4, 0)));
+    }
+
+    #[test]
+    fn weekends_and_holidays_wait_for_the_next_trading_day() {
+        use MarketState::*;
+        assert_eq!(worked_out(at(2020, 7, 3, 10, 0)), (Closed, Premarket, at(2020, 7, 6, 4, 0)));
+        assert_eq!(worked_out(at(2020, 7, 4, 10, 0)), (Closed, Premarket, at(2020, 7, 6, 4, 0)));
+        assert_eq!(worked_out(at(2020, 7, 5, 23, 0)), (Closed, Premarket, at(2020, 7, 6, 4, 0)));
+    }
+
+    #[test]
+    fn early_closes_count_down_to_one_pm() {
+        use MarketState::*;
+        assert_eq!(worked_out(at(2020, 11, 27, 12, 0)), (Open, Postmarket, at(2020, 11, 27, 13, 0)));
+        assert_eq!(worked_out(at(2020, 11, 27, 14, 0)), (Postmarket, Closed, at(2020, 11, 27, 20, 0)));
+    }
+}
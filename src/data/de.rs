@@ -0,0 +1,76 @@
+// Deserializers for the ways Tradier bends its own schema.
+//
+// A list with one element comes back as a bare object, an empty list comes
+// back as `null` (or, in some account endpoints, the string "null"), and
+// numeric fields are `null` for symbols that haven't traded. The streaming
+// API sends most numbers as strings.
+
+use std::str::FromStr;
+
+use chrono::NaiveTime;
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+fn is_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s == "null",
+        _ => false
+    }
+}
+
+/// Accept `null`, a single value or an array of values as a `Vec`.
+pub fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    match value {
+        v if is_null(&v) => Ok(vec![]),
+        Value::Array(values) => values.into_iter()
+            .map(|v| serde_json::from_value(v).map_err(D::Error::custom))
+            .collect(),
+        v => serde_json::from_value(v).map(|t| vec![t]).map_err(D::Error::custom)
+    }
+}
+
+/// Like `Option<T>`, but also treat the string "null" as missing.
+pub fn null_or<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    if is_null(&value) {
+        Ok(None)
+    } else {
+        serde_json::from_value(value).map(Some).map_err(D::Error::custom)
+    }
+}
+
+/// A time of day written as "09:30", as in the calendar and clock.
+pub fn hour_minute<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&s, "%H:%M").map_err(D::Error::custom)
+}
+
+/// A number that may be written as a string, as in streaming events. Null
+/// and the empty string are missing.
+pub fn number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    match value {
+        v if is_null(&v) => Ok(None),
+        Value::String(s) if s.is_empty() => Ok(None),
+        Value::String(s) => s.parse().map(Some).map_err(|_| D::Error::custom(format!("\"{}\" isn't a number", s))),
+        v => serde_json::from_value(v).map(Some).map_err(D::Error::custom)
+    }
+}
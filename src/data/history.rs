@@ -0,0 +1,155 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::data::de;
+
+// API Representation
+
+// `history` is null when there were no trading days in the requested range.
+#[derive(Debug, Deserialize)]
+pub struct HistoryDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    history: Option<HistoryContainer>
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryContainer {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    day: Vec<Bar>
+}
+
+/// Bar is one daily, weekly or monthly OHLCV candle.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Bar {
+    pub date: NaiveDate,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    #[serde(default)]
+    pub volume: u64
+}
+
+impl HistoryDataModel {
+    pub fn bars(&self) -> &[Bar] {
+        self.history.as_ref().map_or(&[], |h| &h.day[..])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bars().is_empty()
+    }
+
+    /// Drop every bar outside `start..=end`.
+    pub fn retain_dates(mut self, start: NaiveDate, end: NaiveDate) -> Self {
+        if let Some(history) = self.history.as_mut() {
+            history.day.retain(|bar| bar.date >= start && bar.date <= end);
+        }
+        self
+    }
+}
+
+// Graph Representation
+
+impl HistoryDataModel {
+    pub fn to_graph_data(&self, width: u16) -> Vec<(f64, f64)> {
+        let bars = self.bars();
+        if bars.is_empty() {
+            return vec![];
+        }
+        (0..width).map(|i| {
+            let pos = (i as f64) / (width as f64);
+            let idx = (pos * bars.len() as f64) as usize;
+            (i as f64, bars[idx].close)
+        }).collect()
+    }
+
+    pub fn min_max(&self) -> (f64, f64) {
+        self.bars().iter().fold((f64::MAX, f64::MIN), |(min, max), bar| {
+            (min.min(bar.close), max.max(bar.close))
+        })
+    }
+
+    pub fn went_up(&self) -> bool {
+        match (self.bars().first(), self.bars().last()) {
+            (Some(first), Some(last)) => first.close <= last.close,
+            _ => true
+        }
+    }
+
+    /// `count` evenly spaced dates across the range, for the x axis.
+    pub fn date_labels(&self, count: usize) -> Vec<String> {
+        let bars = self.bars();
+        if bars.is_empty() || count < 2 {
+            return vec![];
+        }
+        (0..count).map(|i| {
+            let idx = i * (bars.len() - 1) / (count - 1);
+            bars[idx].date.format("%b %d '%y").to_string()
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(json: &str) -> HistoryDataModel {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn history(closes: &[(&str, f64)]) -> HistoryDataModel {
+        let days: Vec<String> = closes.iter()
+            .map(|(date, close)| format!(r#"{{"date":"{}","open":{c},"high":{c},"low":{c},"close":{c},"volume":100}}"#, date, c = close))
+            .collect();
+        model(&format!(r#"{{"history":{{"day":[{}]}}}}"#, days.join(",")))
+    }
+
+    #[test]
+    fn the_fixture_reads() {
+        let history = model(include_str!("../../fixtures/markets/history.json"));
+        assert!(history.bars().len() > 1000);
+        assert_eq!(history.bars()[0].date, NaiveDate::from_ymd(2015, 7, 1));
+    }
+
+    #[test]
+    fn a_single_day_is_an_object() {
+        let history = model(r#"{"history":{"day":{"date":"2020-07-02","open":310.0,"high":312.0,"low":309.0,"close":311.5}}}"#);
+        assert_eq!(history.bars().len(), 1);
+        assert_eq!(history.bars()[0].volume, 0);
+    }
+
+    #[test]
+    fn no_days_are_null() {
+        assert!(model(r#"{"history":null}"#).is_empty());
+        assert!(model(r#"{"history":"null"}"#).is_empty());
+        assert!(model(r#"{"history":{"day":null}}"#).is_empty());
+    }
+
+    #[test]
+    fn retained_dates_include_both_ends() {
+        let history = history(&[("2020-06-29", 1.0), ("2020-06-30", 2.0), ("2020-07-01", 3.0), ("2020-07-02", 4.0)])
+            .retain_dates(NaiveDate::from_ymd(2020, 6, 30), NaiveDate::from_ymd(2020, 7, 1));
+        let dates: Vec<NaiveDate> = history.bars().iter().map(|b| b.date).collect();
+        assert_eq!(dates, vec![NaiveDate::from_ymd(2020, 6, 30), NaiveDate::from_ymd(2020, 7, 1)]);
+    }
+
+    #[test]
+    fn graph_data_spreads_the_closes_across_the_width() {
+        let history = history(&[("2020-06-29", 1.0), ("2020-06-30", 2.0), ("2020-07-01", 3.0), ("2020-07-02", 4.0)]);
+        assert_eq!(history.to_graph_data(8), vec![
+            (0.0, 1.0), (1.0, 1.0), (2.0, 2.0), (3.0, 2.0), (4.0, 3.0), (5.0, 3.0), (6.0, 4.0), (7.0, 4.0)
+        ]);
+        assert_eq!(history.to_graph_data(2), vec![(0.0, 1.0), (1.0, 3.0)]);
+        assert_eq!(history.min_max(), (1.0, 4.0));
+        assert!(history.went_up());
+        assert!(model(r#"{"history":null}"#).to_graph_data(80).is_empty());
+    }
+
+    #[test]
+    fn date_labels_span_first_to_last() {
+        let history = history(&[("2020-06-29", 1.0), ("2020-06-30", 2.0), ("2020-07-01", 3.0), ("2020-07-02", 4.0), ("2020-07-06", 5.0)]);
+        assert_eq!(history.date_labels(3), vec!["Jun 29 '20", "Jul 01 '20", "Jul 06 '20"]);
+        assert!(history.date_labels(1).is_empty());
+        assert!(model(r#"{"history":null}"#).date_labels(3).is_empty());
+    }
+}
@@ -1,12 +1,40 @@
+mod de;
+pub mod account;
+pub mod calendar;
+pub mod chain;
+pub mod clock;
+mod history;
 mod quote;
-mod symbols;
+pub mod search;
+pub mod symbols;
+pub mod symbology;
 mod options;
+pub mod parse;
 pub mod series;
+pub mod stream;
 pub mod watchlist;
 
 
 pub type Quotes = quote::QuotesDataModel;
 pub type Quote = quote::Quote;
-pub type Symbol = symbols::Symbol;
+pub type Security = symbols::Security;
 pub type Option = options::Option;
+pub type OptionType = options::OptionType;
 pub type TimeSeries = series::TimeSeriesDataModel;
+pub type History = history::HistoryDataModel;
+pub type Calendar = calendar::CalendarDataModel;
+pub type Clock = clock::ClockDataModel;
+pub type Securities = search::SecuritiesDataModel;
+pub type Expirations = chain::ExpirationsDataModel;
+pub type Strikes = chain::StrikesDataModel;
+pub type OptionChain = chain::OptionChainDataModel;
+pub type Profile = account::ProfileDataModel;
+pub type Balances = account::BalancesDataModel;
+pub type Positions = account::PositionsDataModel;
+pub type Orders = account::OrdersDataModel;
+pub type AccountHistory = account::AccountHistoryDataModel;
+pub type OrderPreview = account::OrderPreviewDataModel;
+pub type OrderConfirmation = account::OrderConfirmationDataModel;
+pub type StreamSession = stream::StreamSessionDataModel;
+pub type Watchlists = watchlist::WatchlistsDataModel;
+pub type Watchlist = watchlist::WatchlistDataModel;
@@ -1,8 +1,11 @@
 use std::io;
 use std::str::FromStr;
-use chrono::{Date, FixedOffset, TimeZone};
+use serde::Deserialize;
 
-#[derive(Debug, Clone, Copy)]
+use crate::data::parse::{Row, RowError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OptionType { 
     Call,
     Put
@@ -24,20 +27,19 @@ pub struct Option {
 
 impl FromStr for Option { 
     //Root Symbol|Options Closing Type|Options Type|Expiration Date|Explicit Strike Price|Underlying Symbol|Underlying Issue Name|Pending
-    type Err = io::Error;
+    type Err = RowError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let components: Vec<&str> = s.split("|").collect();
-        let option = Option { 
-            closing_type: components[1].parse().unwrap(),
-            options_type: components[2].parse().unwrap(),
-            expiration_date: components[3].parse().unwrap(),
-            strike_price: components[4].parse().unwrap(),
-            underlying_symbol: components[5].parse().unwrap(),
-            underlying_name: components[6].parse().unwrap(),
-            pending: components[7] == "Y"
-        };
-        Ok(option)
+        let row = Row::new(s, 8)?;
+        Ok(Option { 
+            closing_type: row.text(1),
+            options_type: row.parse(2, "Options Type")?,
+            expiration_date: row.text(3),
+            strike_price: row.parse(4, "Explicit Strike Price")?,
+            underlying_symbol: row.text(5),
+            underlying_name: row.text(6),
+            pending: row.flag(7)
+        })
     }
 }
 
@@ -48,7 +50,7 @@ impl FromStr for OptionType {
         match s {
             "C" => Ok(Self::Call),
             "P" => Ok(Self::Put),
-            _ => Err(io::Error::new(io::ErrorKind::Other, "Error parsing option type."))
+            _ => Err(io::Error::other("Error parsing option type."))
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// RowError is why one row of a NASDAQ Trader file couldn't be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowError {
+    /// The row has fewer columns than the file's layout.
+    MissingColumns { expected: usize, found: usize },
+    /// A column doesn't hold what it should. `column` counts from 1.
+    Invalid { column: usize, name: &'static str, value: String }
+}
+
+impl RowError {
+    /// The column at fault, counting from 1. For a short row, the first
+    /// column that's missing.
+    pub fn column(&self) -> usize {
+        match self {
+            RowError::MissingColumns { found, .. } => found + 1,
+            RowError::Invalid { column, .. } => *column
+        }
+    }
+
+    /// What went wrong, without the value, so errors can be counted by kind.
+    pub fn kind(&self) -> String {
+        match self {
+            RowError::MissingColumns { .. } => "missing columns".to_string(),
+            RowError::Invalid { name, .. } => format!("invalid {}", name)
+        }
+    }
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowError::MissingColumns { expected, found } => write!(f, "expected {} columns, found {}", expected, found),
+            RowError::Invalid { name, value, .. } => write!(f, "invalid {} \"{}\"", name, value)
+        }
+    }
+}
+
+/// ParseError is a row that was skipped, and where it is in its file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: String,
+    /// Line number in the file, counting the header as line 1.
+    pub line: usize,
+    pub error: RowError
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.file, self.line, self.error.column(), self.error)
+    }
+}
+
+/// Row is one line of a pipe-delimited file, split into its columns.
+pub struct Row<'a> {
+    columns: Vec<&'a str>
+}
+
+impl<'a> Row<'a> {
+    /// Split `line`, which must have at least `expected` columns.
+    pub fn new(line: &'a str, expected: usize) -> Result<Self, RowError> {
+        let columns: Vec<&str> = line.split('|').collect();
+        if columns.len() < expected {
+            return Err(RowError::MissingColumns { expected, found: columns.len() });
+        }
+        Ok(Row { columns })
+    }
+
+    pub fn text(&self, index: usize) -> String {
+        self.columns[index].trim().to_string()
+    }
+
+    /// A `Y`/`N` column.
+    pub fn flag(&self, index: usize) -> bool {
+        self.columns[index].trim() == "Y"
+    }
+
+    /// Read the column at `index`, called `name` in errors.
+    pub fn parse<T: FromStr>(&self, index: usize, name: &'static str) -> Result<T, RowError> {
+        let value = self.columns[index].trim();
+        value.parse().map_err(|_| RowError::Invalid { column: index + 1, name, value: value.to_string() })
+    }
+}
+
+/// SkipReport is how loading one file went: how many rows made it in, and
+/// the ones that didn't.
+#[derive(Debug, Clone)]
+pub struct SkipReport {
+    pub file: String,
+    pub loaded: usize,
+    pub skipped: Vec<ParseError>
+}
+
+impl SkipReport {
+    pub fn new(file: &str) -> Self {
+        SkipReport { file: file.to_string(), loaded: 0, skipped: vec![] }
+    }
+
+    /// One line on what was rejected and why, or None if nothing was.
+    pub fn summary(&self) -> Option<String> {
+        if self.skipped.is_empty() {
+            return None;
+        }
+        let mut kinds: BTreeMap<String, usize> = BTreeMap::new();
+        for error in &self.skipped {
+            *kinds.entry(error.error.kind()).or_insert(0) += 1;
+        }
+        let reasons: Vec<String> = kinds.iter().map(|(kind, count)| format!("{} {}", count, kind)).collect();
+        Some(format!(
+            "Skipped {} of {} rows in {}: {}.",
+            self.skipped.len(),
+            self.loaded + self.skipped.len(),
+            self.file,
+            reasons.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skipped(line: usize, error: RowError) -> ParseError {
+        ParseError { file: "otherlisted.txt".to_string(), line, error }
+    }
+
+    #[test]
+    fn rows_need_every_column() {
+        assert_eq!(Row::new("A|B|C", 4).err(), Some(RowError::MissingColumns { expected: 4, found: 3 }));
+        assert_eq!(RowError::MissingColumns { expected: 4, found: 3 }.column(), 4);
+        let row = Row::new("A|B|C|D|E", 4).unwrap();
+        assert_eq!(row.text(4), "E");
+    }
+
+    #[test]
+    fn columns_are_trimmed() {
+        let row = Row::new(" SPY |Y| 100 |N", 4).unwrap();
+        assert_eq!(row.text(0), "SPY");
+        assert!(row.flag(1));
+        assert!(!row.flag(3));
+        assert_eq!(row.parse::<u16>(2, "Round Lot Size"), Ok(100));
+    }
+
+    #[test]
+    fn bad_values_name_their_column() {
+        let row = Row::new("SPY|Y|lots|N", 4).unwrap();
+        let error = row.parse::<u16>(2, "Round Lot Size").unwrap_err();
+        assert_eq!(error, RowError::Invalid { column: 3, name: "Round Lot Size", value: "lots".to_string() });
+        assert_eq!(skipped(12, error).to_string(), "otherlisted.txt:12:3: invalid Round Lot Size \"lots\"");
+    }
+
+    #[test]
+    fn summaries_count_skips_by_kind() {
+        let mut report = SkipReport::new("otherlisted.txt");
+        report.loaded = 10;
+        assert_eq!(report.summary(), None);
+        report.skipped = vec![
+            skipped(3, RowError::Invalid { column: 3, name: "Exchange", value: "X".to_string() }),
+            skipped(5, RowError::MissingColumns { expected: 8, found: 2 }),
+            skipped(9, RowError::Invalid { column: 3, name: "Exchange", value: "Y".to_string() })
+        ];
+        assert_eq!(
+            report.summary().unwrap(),
+            "Skipped 3 of 13 rows in otherlisted.txt: 2 invalid Exchange, 1 missing columns."
+        );
+    }
+}
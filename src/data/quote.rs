@@ -1,39 +1,129 @@
 use serde::Deserialize;
 
+use crate::data::de;
+use crate::data::symbology;
+
 #[derive(Debug, Deserialize)]
-pub struct QuotesDataModel { 
-    quotes: Quotes
+pub struct QuotesDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    quotes: Option<Quotes>
 }
 
 impl QuotesDataModel {
-    pub fn quotes(&self) -> &Vec<Quote> {
-        &self.quotes.quote
+    pub fn quotes(&self) -> &[Quote] {
+        self.quotes.as_ref().map_or(&[], |q| &q.quote[..])
+    }
+
+    /// Symbols Tradier didn't recognize, so no quote came back for them.
+    pub fn unmatched_symbols(&self) -> &[String] {
+        self.quotes.as_ref()
+            .and_then(|q| q.unmatched_symbols.as_ref())
+            .map_or(&[], |u| &u.symbol[..])
+    }
+
+    /// Drop every quote whose symbol wasn't asked for.
+    pub fn retain_symbols(mut self, symbols: &[String]) -> Self {
+        if let Some(quotes) = self.quotes.as_mut() {
+            quotes.quote.retain(|q| symbols.iter().any(|s| symbology::same_symbol(s, &q.symbol)));
+        }
+        self
     }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Quotes {
-    pub quote: Vec<Quote>
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    pub quote: Vec<Quote>,
+    #[serde(default, deserialize_with = "de::null_or")]
+    unmatched_symbols: Option<UnmatchedSymbols>
 }
 
+#[derive(Debug, Deserialize)]
+struct UnmatchedSymbols {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    symbol: Vec<String>
+}
+
+// Everything but the symbol can be null for a security that isn't trading.
 #[derive(Debug, Deserialize, Clone)]
-pub struct Quote { 
-    pub ask: f32,
-    pub bid: f32,
+pub struct Quote {
+    pub ask: Option<f32>,
+    pub bid: Option<f32>,
     #[serde(rename(deserialize = "asksize"))]
-    pub ask_size: u32,
+    pub ask_size: Option<u32>,
     #[serde(rename(deserialize = "bidsize"))]
-    pub bid_size: u32,
+    pub bid_size: Option<u32>,
     pub symbol: String,
-    pub volume: u32, 
-    pub week_52_high: f32,
-    pub week_52_low: f32,
+    pub volume: Option<u32>,
+    pub week_52_high: Option<f32>,
+    pub week_52_low: Option<f32>,
     pub open: Option<f32>,
     pub close: Option<f32>,
-    pub last: f32,
+    #[serde(rename(deserialize = "prevclose"))]
+    pub prev_close: Option<f32>,
+    pub last: Option<f32>,
+    #[serde(default)]
     pub description: String,
     #[serde(rename(deserialize = "change"))]
-    pub change_points: f32,
-    pub change_percentage: f32,
+    pub change_points: Option<f32>,
+    pub change_percentage: Option<f32>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(json: &str) -> QuotesDataModel {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn symbols(model: &QuotesDataModel) -> Vec<&str> {
+        model.quotes().iter().map(|q| q.symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn the_fixture_reads() {
+        let quotes = model(include_str!("../../fixtures/markets/quotes.json"));
+        assert!(symbols(&quotes).contains(&"SPY"));
+        assert!(quotes.quotes().iter().all(|q| q.last.is_some()));
+    }
+
+    #[test]
+    fn a_single_quote_is_an_object() {
+        let quotes = model(r#"{"quotes":{"quote":{"symbol":"SPY","last":310.5,"prevclose":308.0,"change":2.5,"change_percentage":0.81}}}"#);
+        assert_eq!(symbols(&quotes), vec!["SPY"]);
+        assert_eq!(quotes.quotes()[0].change_points, Some(2.5));
+        assert!(quotes.unmatched_symbols().is_empty());
+    }
+
+    #[test]
+    fn no_quotes_are_null() {
+        assert!(model(r#"{"quotes":null}"#).quotes().is_empty());
+        assert!(model(r#"{"quotes":"null"}"#).quotes().is_empty());
+        assert!(model(r#"{}"#).quotes().is_empty());
+    }
+
+    #[test]
+    fn numbers_can_be_null() {
+        let quotes = model(r#"{"quotes":{"quote":{"symbol":"XYZ","last":null,"bid":null,"ask":null,"volume":null,"change":null,"change_percentage":null}}}"#);
+        let quote = &quotes.quotes()[0];
+        assert_eq!((quote.last, quote.bid, quote.ask, quote.volume), (None, None, None, None));
+        assert_eq!(quote.description, "");
+    }
+
+    #[test]
+    fn unmatched_symbols_can_be_one_or_many() {
+        let one = model(r#"{"quotes":{"quote":{"symbol":"SPY"},"unmatched_symbols":{"symbol":"XYZ"}}}"#);
+        assert_eq!(one.unmatched_symbols(), ["XYZ".to_string()]);
+        let many = model(r#"{"quotes":{"unmatched_symbols":{"symbol":["XYZ","QQQQ"]}}}"#);
+        assert!(many.quotes().is_empty());
+        assert_eq!(many.unmatched_symbols(), ["XYZ".to_string(), "QQQQ".to_string()]);
+    }
+
+    #[test]
+    fn retained_quotes_match_any_spelling() {
+        let quotes = model(r#"{"quotes":{"quote":[{"symbol":"BRK.B"},{"symbol":"SPY"},{"symbol":"AAPL"}]}}"#);
+        let kept = quotes.retain_symbols(&["brk/b".to_string(), "AAPL".to_string()]);
+        assert_eq!(symbols(&kept), vec!["BRK.B", "AAPL"]);
+    }
+}
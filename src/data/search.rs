@@ -0,0 +1,128 @@
+use serde::Deserialize;
+
+use crate::data::de;
+use crate::data::Security;
+
+// API Representation
+
+// `markets/search` and `markets/lookup` share a shape. `securities` is null
+// when nothing matched.
+#[derive(Debug, Deserialize, Default)]
+pub struct SecuritiesDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    securities: Option<SecuritiesContainer>
+}
+
+#[derive(Debug, Deserialize)]
+struct SecuritiesContainer {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    security: Vec<SymbolMatch>
+}
+
+/// SymbolMatch is one security found by a search or lookup.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SymbolMatch {
+    pub symbol: String,
+    #[serde(default)]
+    pub exchange: String,
+    #[serde(rename(deserialize = "type"))]
+    pub security_type: SecurityType,
+    #[serde(default)]
+    pub description: String
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityType {
+    Stock,
+    Etf,
+    Index,
+    Option,
+    MutualFund,
+    #[serde(other)]
+    Other
+}
+
+impl SecurityType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SecurityType::Stock => "Stock",
+            SecurityType::Etf => "ETF",
+            SecurityType::Index => "Index",
+            SecurityType::Option => "Option",
+            SecurityType::MutualFund => "Mutual fund",
+            SecurityType::Other => "Other"
+        }
+    }
+}
+
+impl SecuritiesDataModel {
+    pub fn matches(&self) -> &[SymbolMatch] {
+        self.securities.as_ref().map_or(&[], |s| &s.security[..])
+    }
+
+    /// Keep the matches `f` accepts.
+    pub fn retain<F: Fn(&SymbolMatch) -> bool>(mut self, f: F) -> Self {
+        if let Some(securities) = self.securities.as_mut() {
+            securities.security.retain(|m| f(m));
+        }
+        self
+    }
+}
+
+// A directory entry as a match, so local and API results can be shown side
+// by side.
+impl From<&Security> for SymbolMatch {
+    fn from(security: &Security) -> Self {
+        SymbolMatch {
+            symbol: security.symbol.clone(),
+            exchange: security.exchange.code().to_string(),
+            security_type: if security.etf { SecurityType::Etf } else { SecurityType::Stock },
+            description: security.security_name.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(json: &str) -> SecuritiesDataModel {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn a_list_of_matches_reads() {
+        let found = model(include_str!("../../fixtures/markets/lookup.json"));
+        assert_eq!(found.matches()[0].symbol, "SPX");
+        assert_eq!(found.matches()[0].security_type, SecurityType::Index);
+        assert!(found.matches().iter().any(|m| m.security_type == SecurityType::MutualFund));
+    }
+
+    #[test]
+    fn a_single_match_is_an_object() {
+        let found = model(r#"{"securities":{"security":{"symbol":"AAPL","exchange":"Q","type":"stock","description":"Apple Inc"}}}"#);
+        assert_eq!(found.matches().len(), 1);
+        assert_eq!(found.matches()[0].security_type.label(), "Stock");
+    }
+
+    #[test]
+    fn no_matches_are_null() {
+        assert!(model(r#"{"securities":null}"#).matches().is_empty());
+        assert!(model(r#"{"securities":"null"}"#).matches().is_empty());
+    }
+
+    #[test]
+    fn unknown_types_are_other() {
+        let found = model(r#"{"securities":{"security":{"symbol":"XYZ","type":"bond"}}}"#);
+        assert_eq!(found.matches()[0].security_type, SecurityType::Other);
+        assert_eq!(found.matches()[0].exchange, "");
+    }
+
+    #[test]
+    fn retain_keeps_what_is_accepted() {
+        let found = model(include_str!("../../fixtures/markets/lookup.json")).retain(|m| m.security_type == SecurityType::Index);
+        let symbols: Vec<&str> = found.matches().iter().map(|m| m.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["SPX", "VIX", "NDX", "DJI"]);
+    }
+}
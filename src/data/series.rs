@@ -1,43 +1,56 @@
-use chrono::{DateTime, FixedOffset};
+use chrono::DateTime;
+use chrono_tz::Tz;
 use serde::Deserialize;
 
-use crate::util;
+use crate::data::de;
 
 // API Representation 
 
+// `series` is null when the market was closed for the requested window.
 #[derive(Debug, Deserialize)]
 pub struct TimeSeriesDataModel {
-    series: TimeSeriesContainer
+    #[serde(default, deserialize_with = "de::null_or")]
+    series: Option<TimeSeriesContainer>
 }
 
 impl TimeSeriesDataModel {
-    pub fn data(&self) -> &Vec<TimeSeriesPoint> {
-        &self.series.data
+    pub fn data(&self) -> &[TimeSeriesPoint] {
+        self.series.as_ref().map_or(&[], |s| &s.data[..])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data().is_empty()
     }
 }
 
 #[derive(Debug, Deserialize)]
 struct  TimeSeriesContainer {
+    #[serde(default, deserialize_with = "de::one_or_many")]
     data: Vec<TimeSeriesPoint>
 }
 
+// Bars carry open/high/low/close and vwap; tick intervals send only the
+// trade price, so the graph is drawn from `price`, which both have.
 #[derive(Debug, Deserialize)]
 pub struct TimeSeriesPoint {
-    pub close: f64,
-    pub high: f64,
-    pub low: f64,
+    pub close: Option<f64>,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
     pub price: f64, 
     #[serde(with = "date_format")]
-    pub time: DateTime<FixedOffset>,
+    pub time: DateTime<Tz>,
     pub timestamp: u32,
     pub volume: u32, 
-    pub vwap: f64
+    pub vwap: Option<f64>
 }
 
 mod date_format {
-    use chrono::{DateTime, FixedOffset};
+    use chrono::{DateTime, NaiveDateTime};
+    use chrono_tz::Tz;
     use serde::{self, Deserialize, Deserializer};
 
+    use crate::util;
+
     // The signature of a deserialize_with function must follow the pattern:
     //
     //    fn deserialize<'de, D>(D) -> Result<T, D::Error>
@@ -47,31 +60,35 @@ mod date_format {
     // although it may also be generic over the output types T.
     pub fn deserialize<'de, D>(
         deserializer: D,
-    ) -> Result<DateTime<FixedOffset>, D::Error>
+    ) -> Result<DateTime<Tz>, D::Error>
     where
         D: Deserializer<'de>,
     {
+        // Times come back as exchange wall-clock time with no offset, e.g.
+        // "2020-07-02T09:30:00", so the offset depends on daylight saving.
         let s = String::deserialize(deserializer)?;
-        let s = format!("{}-05:00", s);
-        
-        DateTime::<FixedOffset>::parse_from_rfc3339(&s).map_err(serde::de::Error::custom)
+        let naive = NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S")
+            .map_err(serde::de::Error::custom)?;
+        util::eastern_datetime(naive.date(), naive.time())
+            .ok_or_else(|| serde::de::Error::custom(format!("{} doesn't exist in Eastern time", s)))
     }
 }
 
 // Graph Representation
 
-pub const TIME_MARKERS: &[&str] = &["9:30", "11:00", "1:00", "2:30", "4:00"]; 
-
 impl TimeSeriesDataModel {
     pub fn to_graph_data(&self, width: u16) -> Vec<(f64, f64)> {
         let data = &self.data();
         let num_points = data.len();
+        if num_points == 0 {
+            return vec![];
+        }
         let mut result = Vec::with_capacity(width as usize);
         for i in 0..width {
             let pos = (i as f64) / (width as f64);
             let x = pos * (num_points as f64);
             let idx = x as usize;
-            let y = data[idx].vwap;
+            let y = data[idx].price;
             result.push((i as f64, y));
         }
         result
@@ -81,18 +98,83 @@ impl TimeSeriesDataModel {
         let mut min = f64::MAX;
         let mut max = f64::MIN;
         for point in self.data() {
-            if point.vwap > max { 
-                max = point.vwap;
+            if point.price > max { 
+                max = point.price;
             }
-            if point.vwap < min { 
-                min = point.vwap;
+            if point.price < min { 
+                min = point.price;
             }
         }
         (min, max)
     }
 
-    pub fn went_up(&self) -> bool { 
+    /// `count` evenly spaced times across the session, for the x axis. They
+    /// come from the data, so early closes and extended hours label correctly.
+    pub fn time_labels(&self, count: usize) -> Vec<String> {
         let data = self.data();
-        return data[0 as usize].vwap <= data.last().unwrap().vwap;
+        if data.is_empty() || count < 2 {
+            return vec![];
+        }
+        (0..count).map(|i| {
+            let idx = i * (data.len() - 1) / (count - 1);
+            data[idx].time.format("%-I:%M").to_string()
+        }).collect()
+    }
+
+    pub fn went_up(&self) -> bool { 
+        match (self.data().first(), self.data().last()) {
+            (Some(first), Some(last)) => first.price <= last.price,
+            _ => true
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_have_only_a_price() {
+        let json = r#"{"series": {"data": [
+            {"time": "2020-07-02T09:30:00", "timestamp": 1593696600, "price": 310.3, "volume": 100},
+            {"time": "2020-07-02T09:30:01", "timestamp": 1593696601, "price": 310.5, "volume": 200}
+        ]}}"#;
+        let series: TimeSeriesDataModel = serde_json::from_str(json).unwrap();
+        assert_eq!(series.data().len(), 2);
+        assert_eq!(series.data()[0].vwap, None);
+        assert_eq!(series.min_max(), (310.3, 310.5));
+        assert!(series.went_up());
+        assert_eq!(series.to_graph_data(2), vec![(0.0, 310.3), (1.0, 310.5)]);
+    }
+
+    #[test]
+    fn bars_keep_their_ohlc() {
+        let json = r#"{"series": {"data": {"time": "2020-07-02T09:30:00", "timestamp": 1593696600,
+            "price": 310.3, "open": 310.1, "high": 310.68, "low": 309.93, "close": 310.5, "volume": 211726, "vwap": 310.37}}}"#;
+        let series: TimeSeriesDataModel = serde_json::from_str(json).unwrap();
+        let bar = &series.data()[0];
+        assert_eq!((bar.high, bar.low, bar.close, bar.vwap), (Some(310.68), Some(309.93), Some(310.5), Some(310.37)));
+    }
+
+    #[test]
+    fn times_parse_across_the_changeover() {
+        let json = r#"{"series": {"data": [
+            {"time": "2020-03-06T09:30:00", "timestamp": 1583505000, "price": 1.0, "volume": 1},
+            {"time": "2020-03-08T01:59:00", "timestamp": 1583650740, "price": 1.0, "volume": 1},
+            {"time": "2020-03-08T03:00:00", "timestamp": 1583650800, "price": 1.0, "volume": 1},
+            {"time": "2020-03-09T09:30:00", "timestamp": 1583760600, "price": 1.0, "volume": 1},
+            {"time": "2020-11-02T16:00:00", "timestamp": 1604350800, "price": 1.0, "volume": 1}
+        ]}}"#;
+        let series: TimeSeriesDataModel = serde_json::from_str(json).unwrap();
+        for point in series.data() {
+            assert_eq!(point.time.timestamp(), i64::from(point.timestamp), "{}", point.time);
+        }
+    }
+
+    #[test]
+    fn skipped_times_are_an_error() {
+        let json = r#"{"series": {"data": {"time": "2020-03-08T02:30:00", "timestamp": 0, "price": 1.0, "volume": 1}}}"#;
+        let err = serde_json::from_str::<TimeSeriesDataModel>(json).unwrap_err();
+        assert!(err.to_string().contains("doesn't exist in Eastern time"), "{}", err);
+    }
+}
@@ -0,0 +1,178 @@
+use serde::Deserialize;
+
+use crate::data::Quote;
+use crate::data::de;
+
+// API Representation
+
+#[derive(Debug, Deserialize)]
+pub struct StreamSessionDataModel {
+    stream: StreamSession
+}
+
+/// StreamSession is a one-off ticket for Tradier's streaming endpoint. It
+/// expires if it isn't used within a few minutes.
+#[derive(Debug, Deserialize, Clone)]
+pub struct StreamSession {
+    pub url: String,
+    #[serde(rename(deserialize = "sessionid"))]
+    pub session_id: String
+}
+
+impl StreamSessionDataModel {
+    pub fn session(&self) -> &StreamSession {
+        &self.stream
+    }
+}
+
+/// StreamEvent is one line of the market event stream. Only the fields the
+/// quote pane shows are kept; other event types are ignored.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StreamEvent {
+    Quote {
+        symbol: String,
+        #[serde(default, deserialize_with = "de::number")]
+        bid: Option<f32>,
+        #[serde(default, deserialize_with = "de::number")]
+        bidsz: Option<u32>,
+        #[serde(default, deserialize_with = "de::number")]
+        ask: Option<f32>,
+        #[serde(default, deserialize_with = "de::number")]
+        asksz: Option<u32>
+    },
+    Trade {
+        symbol: String,
+        #[serde(default, deserialize_with = "de::number")]
+        last: Option<f32>,
+        /// Volume for the day so far.
+        #[serde(default, deserialize_with = "de::number")]
+        cvol: Option<u32>
+    },
+    Summary {
+        symbol: String,
+        #[serde(default, deserialize_with = "de::number")]
+        open: Option<f32>,
+        #[serde(default, deserialize_with = "de::number", rename(deserialize = "prevClose"))]
+        prev_close: Option<f32>
+    },
+    #[serde(other)]
+    Other
+}
+
+impl StreamEvent {
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            StreamEvent::Quote { symbol, .. }
+            | StreamEvent::Trade { symbol, .. }
+            | StreamEvent::Summary { symbol, .. } => Some(symbol),
+            StreamEvent::Other => None
+        }
+    }
+
+    /// Update `quote` with whatever this event says. A trade moves the
+    /// change from the previous close along with the last price.
+    pub fn apply(&self, quote: &mut Quote) {
+        match self {
+            StreamEvent::Quote { bid, bidsz, ask, asksz, .. } => {
+                quote.bid = bid.or(quote.bid);
+                quote.bid_size = bidsz.or(quote.bid_size);
+                quote.ask = ask.or(quote.ask);
+                quote.ask_size = asksz.or(quote.ask_size);
+            },
+            StreamEvent::Trade { last, cvol, .. } => {
+                quote.last = last.or(quote.last);
+                quote.volume = cvol.or(quote.volume);
+                if let (Some(last), Some(prev_close)) = (quote.last, quote.prev_close) {
+                    quote.change_points = Some(last - prev_close);
+                    if prev_close != 0.0 {
+                        quote.change_percentage = Some((last - prev_close) / prev_close * 100.0);
+                    }
+                }
+            },
+            StreamEvent::Summary { open, prev_close, .. } => {
+                quote.open = open.or(quote.open);
+                quote.prev_close = prev_close.or(quote.prev_close);
+            },
+            StreamEvent::Other => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(line: &str) -> StreamEvent {
+        serde_json::from_str(line).unwrap()
+    }
+
+    fn quote() -> Quote {
+        serde_json::from_str(r#"{"symbol":"SPY","last":300.0,"prevclose":300.0,"change":0.0,"change_percentage":0.0}"#).unwrap()
+    }
+
+    #[test]
+    fn numbers_come_as_strings() {
+        match event(r#"{"type":"quote","symbol":"SPY","bid":"310.49","bidsz":"7","ask":"310.51","asksz":"12","bidexch":"Q"}"#) {
+            StreamEvent::Quote { symbol, bid, bidsz, ask, asksz } => {
+                assert_eq!(symbol, "SPY");
+                assert_eq!((bid, bidsz, ask, asksz), (Some(310.49), Some(7), Some(310.51), Some(12)));
+            },
+            other => panic!("expected a quote, got {:?}", other)
+        }
+        match event(r#"{"type":"summary","symbol":"SPY","open":"309.00","prevClose":"308.00","high":"311.00"}"#) {
+            StreamEvent::Summary { open, prev_close, .. } => assert_eq!((open, prev_close), (Some(309.0), Some(308.0))),
+            other => panic!("expected a summary, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn missing_numbers_are_none() {
+        match event(r#"{"type":"trade","symbol":"SPY"}"#) {
+            StreamEvent::Trade { last, cvol, .. } => assert_eq!((last, cvol), (None, None)),
+            other => panic!("expected a trade, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn other_event_types_are_ignored() {
+        let other = event(r#"{"type":"timesale","symbol":"SPY","last":"310.50"}"#);
+        assert!(matches!(other, StreamEvent::Other));
+        assert_eq!(other.symbol(), None);
+        let mut quote = quote();
+        other.apply(&mut quote);
+        assert_eq!(quote.last, Some(300.0));
+    }
+
+    #[test]
+    fn trades_move_the_change_from_the_previous_close() {
+        let mut quote = quote();
+        event(r#"{"type":"summary","symbol":"SPY","open":"301.00","prevClose":"200.00"}"#).apply(&mut quote);
+        assert_eq!((quote.open, quote.prev_close), (Some(301.0), Some(200.0)));
+        // The summary alone leaves the change as the last poll had it.
+        assert_eq!(quote.change_points, Some(0.0));
+
+        event(r#"{"type":"trade","symbol":"SPY","last":"210.00","cvol":"1500"}"#).apply(&mut quote);
+        assert_eq!(quote.last, Some(210.0));
+        assert_eq!(quote.volume, Some(1500));
+        assert_eq!(quote.change_points, Some(10.0));
+        assert_eq!(quote.change_percentage, Some(5.0));
+    }
+
+    #[test]
+    fn quotes_keep_sides_the_event_leaves_out() {
+        let mut quote = quote();
+        event(r#"{"type":"quote","symbol":"SPY","bid":"299.99","bidsz":"3"}"#).apply(&mut quote);
+        event(r#"{"type":"quote","symbol":"SPY","ask":"300.01"}"#).apply(&mut quote);
+        assert_eq!((quote.bid, quote.bid_size, quote.ask, quote.ask_size), (Some(299.99), Some(3), Some(300.01), None));
+    }
+
+    #[test]
+    fn a_zero_previous_close_leaves_the_percentage_alone() {
+        let mut quote = quote();
+        quote.prev_close = Some(0.0);
+        event(r#"{"type":"trade","symbol":"SPY","last":"1.50"}"#).apply(&mut quote);
+        assert_eq!(quote.change_points, Some(1.5));
+        assert_eq!(quote.change_percentage, Some(0.0));
+    }
+}
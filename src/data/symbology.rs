@@ -0,0 +1,182 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// What follows the root of a symbol for anything but plain common stock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Suffix {
+    Class(char),
+    /// A preferred issue, with its series if it has one.
+    Preferred(Option<char>),
+    Warrants,
+    Units,
+    Rights,
+    WhenIssued
+}
+
+/// Ticker is a symbol read from any of the ways it gets written, so the same
+/// security compares equal however it was spelled. Class B Berkshire is
+/// `BRK.B` in the directory files and to Tradier, but people type `BRK/B` or
+/// `BRK B`. Preferreds differ between every source:
+///
+/// | Issue            | ACT      | CQS      | NASDAQ  |
+/// |------------------|----------|----------|---------|
+/// | Class B          | `BRK.B`  | `BRK.B`  | `BRK.B` |
+/// | Preferred D      | `ABR$D`  | `ABRpD`  | `ABR-D` |
+/// | Warrants         | `XYZ.WS` | `XYZ.WS` | `XYZ+`  |
+/// | Units            | `XYZ.U`  | `XYZ.U`  | `XYZ=`  |
+/// | Rights           | `XYZ^`   | `XYZr`   | `XYZ^`  |
+/// | When issued      | `XYZ#`   | `XYZw`   | `XYZ#`  |
+///
+/// Tickers display the way Tradier takes them, which is the consolidated
+/// tape's way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticker {
+    pub root: String,
+    pub suffix: Option<Suffix>
+}
+
+impl Ticker {
+    fn new(root: &str, suffix: Option<Suffix>) -> Result<Self, String> {
+        if root.is_empty() || !root.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("\"{}\" isn't a symbol", root));
+        }
+        Ok(Ticker { root: root.to_uppercase(), suffix })
+    }
+}
+
+impl FromStr for Ticker {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // Case only carries meaning in a CQS suffix: one lower case letter
+        // after an upper case root, like `ABRpD`. Anything else was typed.
+        let cqs = s.find(|c: char| c.is_ascii_lowercase()).is_some_and(|index| {
+            index > 0
+                && !s[..index].contains(|c: char| c.is_ascii_lowercase())
+                && !s[index + 1..].contains(|c: char| c.is_ascii_lowercase())
+        });
+        let s = if cqs { s.to_string() } else { s.to_uppercase() };
+
+        if let Some(index) = s.find(|c| ['.', '/', ' '].contains(&c)) {
+            let rest = s[index + 1..].trim().to_uppercase();
+            let suffix = spelled_suffix(&rest).map_err(|err| format!("{} in \"{}\"", err, s))?;
+            return Ticker::new(&s[..index], suffix);
+        }
+        if let Some(index) = s.find(|c| ['-', '$', '+', '=', '^', '#'].contains(&c)) {
+            let series = s[index + 1..].chars().next().map(|c| c.to_ascii_uppercase());
+            let suffix = match &s[index..index + 1] {
+                "-" | "$" => Suffix::Preferred(series),
+                "+" => Suffix::Warrants,
+                "=" => Suffix::Units,
+                "^" => Suffix::Rights,
+                _ => Suffix::WhenIssued
+            };
+            return Ticker::new(&s[..index], Some(suffix));
+        }
+        if let Some(index) = s.find(|c: char| c.is_ascii_lowercase()) {
+            let suffix = match &s[index..index + 1] {
+                "p" => Suffix::Preferred(s[index + 1..].chars().next().map(|c| c.to_ascii_uppercase())),
+                "r" => Suffix::Rights,
+                "w" => Suffix::WhenIssued,
+                other => return Err(format!("Unknown suffix \"{}\" in \"{}\"", other, s))
+            };
+            return Ticker::new(&s[..index], Some(suffix));
+        }
+        Ticker::new(&s, None)
+    }
+}
+
+/// A suffix written out after a separator, like the `B` in `BRK/B` or the
+/// `PRD` in `ABR PRD`. Anything else is an error rather than a guess.
+fn spelled_suffix(rest: &str) -> Result<Option<Suffix>, String> {
+    let series = |prefix: usize| match rest[prefix..].chars().collect::<Vec<_>>()[..] {
+        [series] => Ok(Some(Suffix::Preferred(Some(series)))),
+        _ => Err(format!("Unknown suffix \"{}\"", rest))
+    };
+    match rest {
+        "" => Ok(None),
+        "WS" => Ok(Some(Suffix::Warrants)),
+        "U" => Ok(Some(Suffix::Units)),
+        "RT" => Ok(Some(Suffix::Rights)),
+        "WI" => Ok(Some(Suffix::WhenIssued)),
+        "P" | "PR" => Ok(Some(Suffix::Preferred(None))),
+        _ if rest.len() == 1 => Ok(rest.chars().next().map(Suffix::Class)),
+        _ if rest.starts_with("PR") => series(2),
+        _ if rest.starts_with('P') => series(1),
+        _ => Err(format!("Unknown suffix \"{}\"", rest))
+    }
+}
+
+impl fmt::Display for Ticker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.root)?;
+        match self.suffix {
+            None => Ok(()),
+            Some(Suffix::Class(class)) => write!(f, ".{}", class),
+            Some(Suffix::Preferred(series)) => write!(f, "p{}", series.map_or(String::new(), |s| s.to_string())),
+            Some(Suffix::Warrants) => write!(f, ".WS"),
+            Some(Suffix::Units) => write!(f, ".U"),
+            Some(Suffix::Rights) => write!(f, "r"),
+            Some(Suffix::WhenIssued) => write!(f, "w")
+        }
+    }
+}
+
+/// `symbol` written the way Tradier takes it, or just upper cased if it
+/// can't be read.
+pub fn normalize(symbol: &str) -> String {
+    symbol.parse::<Ticker>().map_or_else(|_| symbol.trim().to_uppercase(), |ticker| ticker.to_string())
+}
+
+/// Whether `a` and `b` are the same security, however each is written.
+pub fn same_symbol(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_spelling_in_the_table_is_one_symbol() {
+        for &(act, cqs, nasdaq, expected) in &[
+            ("BRK.B", "BRK.B", "BRK.B", "BRK.B"),
+            ("ABR$D", "ABRpD", "ABR-D", "ABRpD"),
+            ("XYZ.WS", "XYZ.WS", "XYZ+", "XYZ.WS"),
+            ("XYZ.U", "XYZ.U", "XYZ=", "XYZ.U"),
+            ("XYZ^", "XYZr", "XYZ^", "XYZr"),
+            ("XYZ#", "XYZw", "XYZ#", "XYZw")
+        ] {
+            for symbol in &[act, cqs, nasdaq] {
+                assert_eq!(normalize(symbol), expected, "{}", symbol);
+            }
+        }
+    }
+
+    #[test]
+    fn typed_spellings_are_the_same_symbol() {
+        assert!(same_symbol("BRK/B", "brk.b"));
+        assert!(same_symbol("BRK B", "BRK.B"));
+        assert!(same_symbol("ABR PRD", "ABR-D"));
+        assert!(same_symbol("ABR/PD", "ABR$D"));
+        assert!(same_symbol("xyz/ws", "XYZ+"));
+        assert!(same_symbol(" spy ", "SPY"));
+        assert!(!same_symbol("BRK.A", "BRK.B"));
+        assert!(!same_symbol("BRK", "BRK.B"));
+    }
+
+    #[test]
+    fn unreadable_symbols_are_upper_cased() {
+        assert!("XYZ.WSA".parse::<Ticker>().is_err());
+        assert_eq!(normalize("xyz.wsa"), "XYZ.WSA");
+        assert_eq!(normalize("ABR PRDX"), "ABR PRDX");
+        assert_eq!(normalize("ABRxD"), "ABRXD");
+    }
+
+    #[test]
+    fn preferreds_without_a_series_read() {
+        assert_eq!("ABR.PR".parse::<Ticker>(), Ok(Ticker { root: "ABR".to_string(), suffix: Some(Suffix::Preferred(None)) }));
+        assert_eq!(normalize("ABR-"), "ABRp");
+    }
+}
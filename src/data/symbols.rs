@@ -1,50 +1,219 @@
 use std::io;
 use std::str::FromStr;
 
-/// Symbol represents the symbol for a single NASDAQ security
+use crate::data::parse::{Row, RowError};
+use crate::data::symbology;
+
+/// NasdaqListed is a row of `nasdaqlisted.txt`, a security listed on NASDAQ.
 #[derive(Debug, Clone)]
-pub struct Symbol { 
+pub struct NasdaqListed { 
     pub symbol: String,
     pub security_name: String,
     pub market_category: MarketCategory,
     pub test_issue: bool,
     pub financial_status: FinancialStatus,
-    pub round_lot_size: u16,
+    pub etf: bool
+}
+
+impl FromStr for NasdaqListed { 
+    //Symbol|Security Name|Market Category|Test Issue|Financial Status|Round Lot Size|ETF|NextShares
+    type Err = RowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let row = Row::new(s, 8)?;
+        Ok(NasdaqListed { 
+            symbol: row.text(0),
+            security_name: row.text(1),
+            market_category: row.parse(2, "Market Category")?,
+            test_issue: row.flag(3),
+            financial_status: row.parse(4, "Financial Status")?,
+            etf: row.flag(6)
+        })
+    }
+}
+
+/// OtherListed is a row of `otherlisted.txt`, a security listed anywhere but
+/// NASDAQ. The file has its own columns:
+///
+/// ACT Symbol|Security Name|Exchange|CQS Symbol|ETF|Round Lot Size|Test Issue|NASDAQ Symbol
+#[derive(Debug, Clone)]
+pub struct OtherListed {
+    /// The symbol as the ACT reporting facility writes it, e.g. `ABR$D`.
+    pub act_symbol: String,
+    pub security_name: String,
+    pub exchange: Exchange,
+    /// The symbol on the consolidated tape, e.g. `ABRpD`.
+    pub cqs_symbol: String,
     pub etf: bool,
-    pub next_shares: bool
+    pub test_issue: bool,
+    /// The symbol as NASDAQ's own systems write it, e.g. `ABR-D`.
+    pub nasdaq_symbol: String
+}
+
+impl FromStr for OtherListed {
+    type Err = RowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let row = Row::new(s, 8)?;
+        Ok(OtherListed {
+            act_symbol: row.text(0),
+            security_name: row.text(1),
+            exchange: row.parse(2, "Exchange")?,
+            cqs_symbol: row.text(3),
+            etf: row.flag(4),
+            test_issue: row.flag(6),
+            nasdaq_symbol: row.text(7)
+        })
+    }
+}
+
+/// Where a security is listed. `otherlisted.txt` codes each exchange with a
+/// letter; NASDAQ listings come from their own file.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Exchange {
+    Nasdaq,
+    Nyse,
+    NyseAmerican,
+    NyseArca,
+    CboeBzx,
+    Iex
 }
 
-impl FromStr for Symbol { 
+impl Exchange {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Exchange::Nasdaq => "NASDAQ",
+            Exchange::Nyse => "NYSE",
+            Exchange::NyseAmerican => "NYSE American",
+            Exchange::NyseArca => "NYSE Arca",
+            Exchange::CboeBzx => "Cboe BZX",
+            Exchange::Iex => "IEX"
+        }
+    }
+
+    /// The one-letter code, which Tradier uses too.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Exchange::Nasdaq => "Q",
+            Exchange::Nyse => "N",
+            Exchange::NyseAmerican => "A",
+            Exchange::NyseArca => "P",
+            Exchange::CboeBzx => "Z",
+            Exchange::Iex => "V"
+        }
+    }
+}
+
+impl FromStr for Exchange {
     type Err = io::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let components: Vec<&str> = s.split("|").collect();
-        let round_lot_size: u16;
-        if let Ok(result) = components[5].parse::<u16>() {
-            round_lot_size = result;
-        } else { 
-            round_lot_size = 100;
+        match s {
+            "Q" => Ok(Self::Nasdaq),
+            "N" => Ok(Self::Nyse),
+            "A" => Ok(Self::NyseAmerican),
+            "P" => Ok(Self::NyseArca),
+            "Z" => Ok(Self::CboeBzx),
+            "V" => Ok(Self::Iex),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown exchange code \"{}\"", s)))
         }
-        let symbol = Symbol { 
-            symbol: components[0].parse().unwrap(),
-            security_name: components[1].parse().unwrap(),
-            market_category: components[2].parse().unwrap(),
-            test_issue: components[3] == "Y",
-            financial_status: components[4].parse().unwrap(),
-            round_lot_size,
-            etf: components[6] == "Y",
-            next_shares: components[7] == "Y"
+    }
+}
+
+/// The directory file a security came from, with everything it said.
+#[derive(Debug, Clone)]
+pub enum Listing {
+    Nasdaq(NasdaqListed),
+    Other(OtherListed)
+}
+
+/// Security is a listing from either directory file, with the fields the two
+/// have in common pulled out.
+#[derive(Debug, Clone)]
+pub struct Security {
+    /// The symbol as Tradier writes it, which is what quotes, charts and the
+    /// watchlist all go by.
+    pub symbol: String,
+    pub security_name: String,
+    pub exchange: Exchange,
+    pub etf: bool,
+    pub test_issue: bool,
+    pub listing: Listing
+}
+
+impl Security {
+    /// Every symbol the security goes by, as written in the directory too.
+    /// Listings elsewhere can be written differently on the consolidated
+    /// tape and at NASDAQ.
+    pub fn symbols(&self) -> Vec<&str> {
+        let written = match &self.listing {
+            Listing::Nasdaq(nasdaq) => vec![&nasdaq.symbol],
+            Listing::Other(other) => vec![&other.act_symbol, &other.cqs_symbol, &other.nasdaq_symbol]
         };
-        Ok(symbol)
+        let mut symbols = vec![self.symbol.as_str()];
+        for symbol in written {
+            if !symbol.is_empty() && !symbols.contains(&symbol.as_str()) {
+                symbols.push(symbol);
+            }
+        }
+        symbols
+    }
+
+    /// Where the security trades. For NASDAQ listings that's the market
+    /// tier, along with any trouble NASDAQ has flagged with the issuer.
+    pub fn market(&self) -> String {
+        match &self.listing {
+            Listing::Nasdaq(nasdaq) => match nasdaq.financial_status.warning() {
+                Some(warning) => format!("{}, {}", nasdaq.market_category.name(), warning),
+                None => nasdaq.market_category.name().to_string()
+            },
+            Listing::Other(_) => self.exchange.name().to_string()
+        }
+    }
+}
+
+impl From<NasdaqListed> for Security {
+    fn from(listed: NasdaqListed) -> Self {
+        Security {
+            symbol: symbology::normalize(&listed.symbol),
+            security_name: listed.security_name.clone(),
+            exchange: Exchange::Nasdaq,
+            etf: listed.etf,
+            test_issue: listed.test_issue,
+            listing: Listing::Nasdaq(listed)
+        }
+    }
+}
+
+impl From<OtherListed> for Security {
+    fn from(listed: OtherListed) -> Self {
+        Security {
+            // The CQS column is the least ambiguous; a few rows leave it out.
+            symbol: symbology::normalize(if listed.cqs_symbol.is_empty() { &listed.act_symbol } else { &listed.cqs_symbol }),
+            security_name: listed.security_name.clone(),
+            exchange: listed.exchange,
+            etf: listed.etf,
+            test_issue: listed.test_issue,
+            listing: Listing::Other(listed)
+        }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum MarketCategory { 
     GlobalSelectMarketSM,
     GlobalMarketSM,
-    CapitalMarket,
-    Unknown
+    CapitalMarket
+}
+
+impl MarketCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarketCategory::GlobalSelectMarketSM => "NASDAQ Global Select",
+            MarketCategory::GlobalMarketSM => "NASDAQ Global Market",
+            MarketCategory::CapitalMarket => "NASDAQ Capital Market"
+        }
+    }
 }
 
 impl FromStr for MarketCategory { 
@@ -55,12 +224,12 @@ impl FromStr for MarketCategory {
             "Q" => Ok(Self::GlobalSelectMarketSM),
             "G" => Ok(Self::GlobalMarketSM),
             "S" => Ok(Self::CapitalMarket),
-            _ => Ok(Self::Unknown)
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown market category \"{}\"", s)))
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum FinancialStatus {
     Deficient, 
     Delinquent,
@@ -69,8 +238,23 @@ pub enum FinancialStatus {
     DeficientAndBankrupt,
     DeficientAndDelinquent,
     DelinquentAndBankrupt,
-    DeficientDelinquentAndBankrupt,
-    Unknown
+    DeficientDelinquentAndBankrupt
+}
+
+impl FinancialStatus {
+    /// What's wrong, or None for an issuer in good standing.
+    pub fn warning(&self) -> Option<&'static str> {
+        match self {
+            FinancialStatus::Deficient => Some("deficient"),
+            FinancialStatus::Delinquent => Some("delinquent"),
+            FinancialStatus::Bankrupt => Some("bankrupt"),
+            FinancialStatus::DeficientAndBankrupt => Some("deficient and bankrupt"),
+            FinancialStatus::DeficientAndDelinquent => Some("deficient and delinquent"),
+            FinancialStatus::DelinquentAndBankrupt => Some("delinquent and bankrupt"),
+            FinancialStatus::DeficientDelinquentAndBankrupt => Some("deficient, delinquent and bankrupt"),
+            FinancialStatus::Normal => None
+        }
+    }
 }
 
 impl FromStr for FinancialStatus { 
@@ -86,8 +270,76 @@ impl FromStr for FinancialStatus {
             "H" => Ok(Self::DeficientAndDelinquent),
             "J" => Ok(Self::DelinquentAndBankrupt),
             "K" => Ok(Self::DeficientDelinquentAndBankrupt),
-            _ => Ok(Self::Unknown)
-            
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, format!("unknown financial status \"{}\"", s)))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APPLE: &str = "AAPL|Apple Inc. - Common Stock|Q|N|N|100|N|N";
+    const ARBOR: &str = "ABR$D|Arbor Realty Trust 6.375% Series D Preferred|N|ABRpD|N|100|N|ABR-D";
+
+    #[test]
+    fn exchange_codes_round_trip() {
+        for exchange in &[Exchange::Nasdaq, Exchange::Nyse, Exchange::NyseAmerican, Exchange::NyseArca, Exchange::CboeBzx, Exchange::Iex] {
+            assert_eq!(exchange.code().parse::<Exchange>().unwrap(), *exchange);
+        }
+    }
+
+    #[test]
+    fn unknown_exchange_codes_are_errors() {
+        assert!("X".parse::<Exchange>().is_err());
+        assert!("".parse::<Exchange>().is_err());
+        let row = "XYZ|XYZ Corp|X|XYZ|N|100|N|XYZ".parse::<OtherListed>().unwrap_err();
+        assert_eq!(row, RowError::Invalid { column: 3, name: "Exchange", value: "X".to_string() });
+    }
+
+    #[test]
+    fn nasdaq_rows_read_their_own_columns() {
+        let listed: NasdaqListed = APPLE.parse().unwrap();
+        assert_eq!(listed.symbol, "AAPL");
+        assert_eq!(listed.security_name, "Apple Inc. - Common Stock");
+        assert_eq!(listed.market_category, MarketCategory::GlobalSelectMarketSM);
+        assert_eq!(listed.financial_status, FinancialStatus::Normal);
+        assert!(!listed.test_issue && !listed.etf);
+    }
+
+    #[test]
+    fn other_rows_read_their_own_columns() {
+        let listed: OtherListed = "SPY|SPDR S&P 500 ETF Trust|P|SPY|Y|100|N|SPY".parse().unwrap();
+        assert_eq!(listed.exchange, Exchange::NyseArca);
+        assert!(listed.etf && !listed.test_issue);
+        let security = Security::from(listed);
+        assert_eq!(security.market(), "NYSE Arca");
+        assert!(security.etf);
+    }
+
+    #[test]
+    fn securities_answer_to_every_spelling() {
+        let security = Security::from(ARBOR.parse::<OtherListed>().unwrap());
+        assert_eq!(security.exchange, Exchange::Nyse);
+        assert_eq!(security.symbols(), vec!["ABRpD", "ABR$D", "ABR-D"]);
+    }
+
+    #[test]
+    fn nasdaq_markets_say_what_is_wrong() {
+        let apple = Security::from(APPLE.parse::<NasdaqListed>().unwrap());
+        assert_eq!(apple.exchange, Exchange::Nasdaq);
+        assert_eq!(apple.market(), "NASDAQ Global Select");
+        let troubled = Security::from("XYZ|XYZ Corp|S|N|H|100|N|N".parse::<NasdaqListed>().unwrap());
+        assert_eq!(troubled.market(), "NASDAQ Capital Market, deficient and delinquent");
+    }
+
+    #[test]
+    fn unknown_nasdaq_codes_are_errors() {
+        assert!("X".parse::<MarketCategory>().is_err());
+        assert!("X".parse::<FinancialStatus>().is_err());
+        let category = "XYZ|XYZ Corp|X|N|N|100|N|N".parse::<NasdaqListed>().unwrap_err();
+        assert_eq!(category, RowError::Invalid { column: 3, name: "Market Category", value: "X".to_string() });
+        let status = "XYZ|XYZ Corp|Q|N||100|N|N".parse::<NasdaqListed>().unwrap_err();
+        assert_eq!(status, RowError::Invalid { column: 5, name: "Financial Status", value: "".to_string() });
+    }
+}
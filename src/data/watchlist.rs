@@ -1,7 +1,19 @@
-use crate::data::Symbol;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::Security;
+use crate::data::de;
+use crate::data::search::SymbolMatch;
+use crate::data::symbology;
 // use crate::cli::Listable;
 
-const DEFAULT: [&str; 10] = [
+/// The watchlist everyone has. Tradier makes one with this name for every
+/// user, and it's the one shown until others are saved.
+pub const DEFAULT_NAME: &str = "default";
+
+/// The symbols the default watchlist starts with.
+pub const DEFAULT: [&str; 10] = [
     "SPY",
     "TSLA",
     "DIS",
@@ -14,9 +26,325 @@ const DEFAULT: [&str; 10] = [
     "AMZN",
 ];
 
-pub fn get_watch_list(s: &[Symbol]) -> Vec<Symbol> { 
-    s.to_vec()
-        .into_iter()
-        .filter(|s| DEFAULT.iter().any(|x| *x == s.symbol.to_uppercase()))
+pub fn default_tickers() -> Vec<String> {
+    DEFAULT.iter().map(|s| s.to_string()).collect()
+}
+
+/// Where what's known about a watchlist entry came from.
+#[derive(Debug, Clone)]
+pub enum EntrySource {
+    /// Listed in the symbol directory.
+    Directory(Security),
+    /// Missing from the directory, but the provider's lookup knows it: an
+    /// index, an OTC name, or something the files left out.
+    Lookup(SymbolMatch),
+    /// The provider's lookup has never heard of it.
+    NotFound,
+    /// Missing from the directory and not looked up yet, or the lookup
+    /// failed, as it does offline.
+    Unverified
+}
+
+/// WatchlistEntry is one ticker on the watchlist. Entries stand on their own,
+/// with or without a directory listing behind them.
+#[derive(Debug, Clone)]
+pub struct WatchlistEntry {
+    pub symbol: String,
+    pub source: EntrySource
+}
+
+impl WatchlistEntry {
+    pub fn is_unverified(&self) -> bool {
+        matches!(self.source, EntrySource::Unverified)
+    }
+}
+
+/// What the provider's lookup said about symbols missing from the directory:
+/// the match, or None if it found nothing.
+pub type Lookups = HashMap<String, Option<SymbolMatch>>;
+
+/// An entry for each of `tickers`, in watchlist order, however each ticker is
+/// written. Tickers missing from the directory are kept, with whatever
+/// `lookups` knows about them.
+pub fn get_watch_list(securities: &[Security], tickers: &[String], lookups: &Lookups) -> Vec<WatchlistEntry> {
+    tickers.iter()
+        .map(|t| {
+            let symbol = symbology::normalize(t);
+            let source = match securities.iter().find(|s| s.symbol == symbol) {
+                Some(security) => EntrySource::Directory(security.clone()),
+                None => match lookups.get(&symbol) {
+                    Some(Some(found)) => EntrySource::Lookup(found.clone()),
+                    Some(None) => EntrySource::NotFound,
+                    None => EntrySource::Unverified
+                }
+            };
+            WatchlistEntry { symbol, source }
+        })
         .collect()
-} 
\ No newline at end of file
+}
+
+// Saved watchlists
+
+/// SavedWatchlist is a named watchlist as kept in the watchlists file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedWatchlist {
+    pub name: String,
+    pub symbols: Vec<String>,
+    /// Keep the watchlist in step with the Tradier one of the same name,
+    /// creating it there if need be. The default watchlist always is.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sync: bool,
+    /// The symbols as of the last sync with Tradier, if there's been one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced: Option<Vec<String>>
+}
+
+impl SavedWatchlist {
+    pub fn syncs(&self) -> bool {
+        self.sync || self.name == DEFAULT_NAME
+    }
+}
+
+/// WatchlistsFile is every saved watchlist and which one is showing. Lists
+/// are added by editing the file, which looks like:
+///
+/// ```json
+/// {
+///   "active": "tech",
+///   "watchlists": [
+///     { "name": "default", "symbols": ["SPY", "DIS"] },
+///     { "name": "tech", "symbols": ["AAPL", "MSFT"], "sync": true },
+///     { "name": "earnings this week", "symbols": ["NKE"] }
+///   ]
+/// }
+/// ```
+///
+/// `synced` is written back by the app and can be left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistsFile {
+    pub active: String,
+    pub watchlists: Vec<SavedWatchlist>
+}
+
+impl Default for WatchlistsFile {
+    fn default() -> Self {
+        WatchlistsFile {
+            active: DEFAULT_NAME.to_string(),
+            watchlists: vec![SavedWatchlist { name: DEFAULT_NAME.to_string(), symbols: default_tickers(), sync: false, synced: None }]
+        }
+    }
+}
+
+impl WatchlistsFile {
+    /// The watchlist to show: the active one, or the first if the active one
+    /// is gone. There's always one, since an empty file gets the default.
+    pub fn active(&self) -> SavedWatchlist {
+        self.find(&self.active)
+            .or_else(|| self.watchlists.first())
+            .cloned()
+            .unwrap_or_else(|| WatchlistsFile::default().watchlists.remove(0))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&SavedWatchlist> {
+        self.watchlists.iter().find(|w| w.name == name)
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut SavedWatchlist> {
+        self.watchlists.iter_mut().find(|w| w.name == name)
+    }
+
+    /// The name of the watchlist after `name`, or before it, wrapping around.
+    pub fn neighbour(&self, name: &str, forward: bool) -> Option<&str> {
+        let len = self.watchlists.len();
+        let index = self.watchlists.iter().position(|w| w.name == name)?;
+        let index = if forward { (index + 1) % len } else { (index + len - 1) % len };
+        Some(&self.watchlists[index].name)
+    }
+}
+
+// API Representation
+
+// `watchlists` is null when the user has none.
+#[derive(Debug, Deserialize)]
+pub struct WatchlistsDataModel {
+    #[serde(default, deserialize_with = "de::null_or")]
+    watchlists: Option<WatchlistSummaries>
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchlistSummaries {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    watchlist: Vec<WatchlistSummary>
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WatchlistSummary {
+    pub id: String,
+    pub name: String
+}
+
+impl WatchlistsDataModel {
+    pub fn watchlists(&self) -> &[WatchlistSummary] {
+        self.watchlists.as_ref().map_or(&[], |w| &w.watchlist[..])
+    }
+
+    pub fn find(&self, name: &str) -> Option<&WatchlistSummary> {
+        self.watchlists().iter().find(|w| w.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// WatchlistDataModel is one watchlist and its symbols. `items` is null for
+/// an empty list.
+#[derive(Debug, Deserialize)]
+pub struct WatchlistDataModel {
+    watchlist: WatchlistModel
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchlistModel {
+    id: String,
+    #[serde(default, deserialize_with = "de::null_or")]
+    items: Option<WatchlistItems>
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchlistItems {
+    #[serde(default, deserialize_with = "de::one_or_many")]
+    item: Vec<WatchlistItem>
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchlistItem {
+    symbol: String
+}
+
+impl WatchlistDataModel {
+    pub fn id(&self) -> &str {
+        &self.watchlist.id
+    }
+
+    pub fn symbols(&self) -> Vec<String> {
+        self.watchlist.items.as_ref()
+            .map_or(vec![], |items| items.item.iter().map(|i| symbology::normalize(&i.symbol)).collect())
+    }
+}
+
+// Sync
+
+/// Merge is the result of reconciling local and remote edits to a watchlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    pub symbols: Vec<String>,
+    /// What changed on the other side since the last sync.
+    pub added_remotely: Vec<String>,
+    pub removed_remotely: Vec<String>
+}
+
+/// Three-way merge of a watchlist edited in two places since `base`, the
+/// symbols both sides last agreed on. Every addition from either side is
+/// kept, and a removal on either side wins over the other side leaving the
+/// symbol alone. The remote order is kept, with local additions at the end.
+pub fn merge(base: &[String], local: &[String], remote: &[String]) -> Merge {
+    let contains = |list: &[String], symbol: &String| list.iter().any(|s| symbology::same_symbol(s, symbol));
+    let removed_locally = |symbol: &String| contains(base, symbol) && !contains(local, symbol);
+    let mut symbols: Vec<String> = remote.iter()
+        .filter(|s| !removed_locally(s))
+        .cloned()
+        .collect();
+    for symbol in local {
+        let added_locally = !contains(base, symbol);
+        if added_locally && !contains(&symbols, symbol) {
+            symbols.push(symbol.clone());
+        }
+    }
+    Merge {
+        added_remotely: remote.iter().filter(|s| !contains(base, s) && !contains(local, s)).cloned().collect(),
+        removed_remotely: base.iter().filter(|s| !contains(remote, s) && contains(local, s)).cloned().collect(),
+        symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(symbols: &[&str]) -> Vec<String> {
+        symbols.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn additions_on_both_sides_are_kept() {
+        let merged = merge(&list(&["SPY"]), &list(&["SPY", "AAPL"]), &list(&["SPY", "MSFT"]));
+        assert_eq!(merged.symbols, list(&["SPY", "MSFT", "AAPL"]));
+        assert_eq!(merged.added_remotely, list(&["MSFT"]));
+        assert!(merged.removed_remotely.is_empty());
+    }
+
+    #[test]
+    fn a_local_removal_wins_over_an_untouched_remote() {
+        let merged = merge(&list(&["SPY", "AAPL"]), &list(&["SPY"]), &list(&["SPY", "AAPL"]));
+        assert_eq!(merged.symbols, list(&["SPY"]));
+        assert!(merged.added_remotely.is_empty());
+        assert!(merged.removed_remotely.is_empty());
+    }
+
+    #[test]
+    fn a_remote_removal_wins_over_an_untouched_local() {
+        let merged = merge(&list(&["SPY", "AAPL"]), &list(&["SPY", "AAPL"]), &list(&["SPY"]));
+        assert_eq!(merged.symbols, list(&["SPY"]));
+        assert_eq!(merged.removed_remotely, list(&["AAPL"]));
+    }
+
+    #[test]
+    fn adding_and_removing_the_same_symbol() {
+        // Added here, then removed again before a sync: Tradier never sees it.
+        let merged = merge(&list(&["SPY"]), &list(&["SPY"]), &list(&["SPY"]));
+        assert_eq!(merged.symbols, list(&["SPY"]));
+        // Removed here from a base saved in another spelling: still the same
+        // symbol, so the removal stands.
+        let merged = merge(&list(&["BRK/B"]), &list(&[]), &list(&["BRK.B"]));
+        assert!(merged.symbols.is_empty());
+        // Added on both sides: kept once, in Tradier's spelling.
+        let merged = merge(&list(&[]), &list(&["BRK/B"]), &list(&["BRK.B"]));
+        assert_eq!(merged.symbols, list(&["BRK.B"]));
+        assert!(merged.added_remotely.is_empty());
+        // Removed on both sides.
+        let merged = merge(&list(&["SPY", "AAPL"]), &list(&["SPY"]), &list(&["SPY"]));
+        assert_eq!(merged.symbols, list(&["SPY"]));
+        assert!(merged.removed_remotely.is_empty());
+    }
+
+    #[test]
+    fn remote_order_is_kept_with_local_additions_last() {
+        let base = list(&["SPY", "AAPL", "MSFT"]);
+        let local = list(&["TSLA", "MSFT", "SPY", "AAPL", "AMD"]);
+        let remote = list(&["MSFT", "AAPL", "SPY"]);
+        assert_eq!(merge(&base, &local, &remote).symbols, list(&["MSFT", "AAPL", "SPY", "TSLA", "AMD"]));
+    }
+
+    #[test]
+    fn only_the_default_and_chosen_lists_sync() {
+        let file: WatchlistsFile = serde_json::from_str(r#"{
+            "active": "tech",
+            "watchlists": [
+                { "name": "default", "symbols": ["SPY", "DIS"] },
+                { "name": "tech", "symbols": ["AAPL", "MSFT"], "sync": true },
+                { "name": "earnings this week", "symbols": ["NKE"] }
+            ]
+        }"#).unwrap();
+        let syncs: Vec<bool> = file.watchlists.iter().map(|w| w.syncs()).collect();
+        assert_eq!(syncs, vec![true, true, false]);
+        assert_eq!(file.active().name, "tech");
+    }
+
+    #[test]
+    fn sync_is_only_written_when_set() {
+        let mut saved = WatchlistsFile::default().active();
+        assert!(saved.syncs());
+        assert_eq!(serde_json::to_value(&saved).unwrap().get("sync"), None);
+        saved.name = "tech".to_string();
+        assert!(!saved.syncs());
+        saved.sync = true;
+        assert_eq!(serde_json::to_value(&saved).unwrap()["sync"], true);
+    }
+}
@@ -1,13 +1,15 @@
-mod api;
-mod cli;
-mod data;
-mod util;
-
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::Mutex;
-use chrono::{DateTime, Datelike, Timelike, TimeZone, Utc};
+use structopt::StructOpt;
+
+use reqwest::Url;
 
+use stonks_cli::{api, cli};
+use api::{AccountProvider, ApiConfig, Environment, MarketDataProvider, TradierClient, FixtureProvider};
+use api::retry::RetryPolicy;
 use cli::{
     App,
     Key,
@@ -15,10 +17,98 @@ use cli::{
     ui
 };
 
+#[derive(Debug, StructOpt)]
+#[structopt(name = "stonks_cli", about = "Watch the market from your terminal.")]
+struct Opt {
+    /// Serve market data from canned Tradier responses in this directory
+    /// instead of calling the API.
+    #[structopt(long, parse(from_os_str))]
+    fixtures: Option<PathBuf>,
+
+    /// Tradier environment: `sandbox`, `production`, or the base URL of a
+    /// stand-in server.
+    #[structopt(long = "env", env = "TRADIER_ENV", default_value = "sandbox")]
+    environment: Environment,
+
+    /// Base URL for every Tradier request. Overrides `--env`.
+    #[structopt(long, env = "TRADIER_BASE_URL")]
+    base_url: Option<Url>,
+
+    /// Tradier API access token.
+    #[structopt(long, env = "TRADIER_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Brokerage account to show. Defaults to the first account on the
+    /// profile.
+    #[structopt(long = "account", env = "TRADIER_ACCOUNT_ID")]
+    account_id: Option<String>,
+
+    /// Allow placing orders in the production environment. Without it, the
+    /// order ticket can preview live orders but not submit them.
+    #[structopt(long)]
+    allow_live_orders: bool,
+
+    /// Seconds to wait for a connection to Tradier.
+    #[structopt(long, env = "TRADIER_CONNECT_TIMEOUT", default_value = "5")]
+    connect_timeout: u64,
+
+    /// Seconds a Tradier request may take in total.
+    #[structopt(long, env = "TRADIER_TIMEOUT", default_value = "15")]
+    timeout: u64,
+
+    /// Times a request is retried after a connection error, 5xx or 429.
+    #[structopt(long, env = "TRADIER_MAX_RETRIES", default_value = "3")]
+    max_retries: u32,
+
+    /// File the named watchlists are kept in. Defaults to watchlists.json
+    /// in the user's config directory. Lists other than "default" only
+    /// sync with Tradier when they have "sync": true.
+    #[structopt(long, env = "STONKS_WATCHLISTS", parse(from_os_str))]
+    watchlists: Option<PathBuf>,
+
+    /// Consecutive failures before requests are paused.
+    #[structopt(long, env = "TRADIER_BREAKER_THRESHOLD", default_value = "5")]
+    breaker_threshold: u32,
+
+    /// Seconds requests stay paused once the breaker opens.
+    #[structopt(long, env = "TRADIER_BREAKER_COOLDOWN", default_value = "30")]
+    breaker_cooldown: u64,
+}
+
+impl Opt {
+    fn api_config(&self) -> ApiConfig {
+        ApiConfig {
+            environment: self.environment.clone().with_base_url(self.base_url.clone()),
+            api_key: self.api_key.clone(),
+            connect_timeout: Duration::from_secs(self.connect_timeout),
+            timeout: Duration::from_secs(self.timeout),
+            retry: RetryPolicy { max_retries: self.max_retries, ..RetryPolicy::default() },
+            breaker_threshold: self.breaker_threshold,
+            breaker_cooldown: Duration::from_secs(self.breaker_cooldown),
+            allow_live_orders: self.allow_live_orders
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+    let opt = Opt::from_args();
+    let (provider, accounts): (Arc<dyn MarketDataProvider>, Arc<dyn AccountProvider>) = match &opt.fixtures {
+        Some(dir) => {
+            let fixtures = Arc::new(FixtureProvider::new(dir));
+            (fixtures.clone(), fixtures)
+        },
+        None => {
+            let client = Arc::new(TradierClient::new(opt.api_config())?);
+            (client.clone(), client)
+        }
+    };
 
-    let app = Arc::new(Mutex::new(App::new()));
+    let mut app = App::new(provider, accounts);
+    app.account.account_number = opt.account_id.clone();
+    app.watchlists_path = opt.watchlists.clone();
+    let app = Arc::new(Mutex::new(app));
     let mut terminal = cli::initialize(Arc::clone(&app)).await?;
 
     let mut events = Events::new();
@@ -32,7 +122,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             match event {
                 Event::Input(k) => match k {
                     Key::Char(c) => {
-                        lock.on_key(c);
+                        lock.on_key(c, Arc::clone(&app));
                     },
                     Key::Up => { 
                         lock.on_up(Arc::clone(&app));
@@ -40,17 +130,67 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     Key::Down => {
                         lock.on_down(Arc::clone(&app));
                     },
+                    Key::Left => {
+                        lock.on_left();
+                    },
+                    Key::Right => {
+                        lock.on_right();
+                    },
+                    Key::Backspace => {
+                        lock.on_backspace();
+                    },
+                    Key::Esc => {
+                        lock.on_escape(Arc::clone(&app));
+                    },
                     _ => {}
                 },
                 Event::Tick => {
-                    lock.on_tick();
+                    lock.on_tick(Arc::clone(&app));
                 }
             }
         }
 
+        // 'q' is text while typing a search, not a way out.
+        if lock.is_typing() {
+            events.disable_exit_key();
+        } else {
+            events.enable_exit_key();
+        }
+
         if lock.should_quit {
             break;
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One test, so nothing else reads TRADIER_BASE_URL while it's changed.
+    #[test]
+    fn tradier_base_url_overrides_env() {
+        std::env::remove_var("TRADIER_BASE_URL");
+        let opt = Opt::from_iter_safe(&["stonks_cli", "--env", "production", "--timeout", "20"]).unwrap();
+        let config = opt.api_config();
+        assert_eq!(config.environment, Environment::Production);
+        assert_eq!(config.timeout, Duration::from_secs(20));
+
+        std::env::set_var("TRADIER_BASE_URL", "http://127.0.0.1:8089/v1");
+        let opt = Opt::from_iter_safe(&["stonks_cli", "--env", "production"]).unwrap();
+        std::env::remove_var("TRADIER_BASE_URL");
+        let config = opt.api_config();
+        assert_eq!(config.base_url().as_str(), "http://127.0.0.1:8089/v1");
+        assert!(!config.is_live());
+
+        // Pointing the sandbox at the production host is still live.
+        std::env::set_var("TRADIER_BASE_URL", "https://api.tradier.com/v1");
+        let opt = Opt::from_iter_safe(&["stonks_cli", "--env", "sandbox"]).unwrap();
+        std::env::remove_var("TRADIER_BASE_URL");
+        let config = opt.api_config();
+        assert!(matches!(config.environment, Environment::Custom(_)));
+        assert!(config.is_live());
+        assert!(!config.allow_live_orders);
+    }
+}
@@ -0,0 +1,266 @@
+// NYSE trading calendar computed from the exchange's holiday rules, for when
+// the calendar API can't be reached. It covers the recurring holidays and
+// early closes; one-off closures (national days of mourning, storms) only
+// come from the API.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+
+/// Early closes end the regular session at 1:00 PM.
+pub const EARLY_CLOSE: (u32, u32) = (13, 0);
+
+pub fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+pub fn is_trading_day(date: NaiveDate) -> bool {
+    !is_weekend(date) && holiday(date).is_none()
+}
+
+/// TradingCalendar steps between trading days. Calendars only say which
+/// days trade; the stepping is shared.
+pub trait TradingCalendar {
+    fn is_trading_day(&self, date: NaiveDate) -> bool;
+
+    /// `date` if it's a trading day, otherwise the one before it.
+    fn last_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        if self.is_trading_day(date) {
+            date
+        } else {
+            self.previous_trading_day(date)
+        }
+    }
+
+    /// The closest trading day strictly before `date`.
+    fn previous_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date.pred();
+        while !self.is_trading_day(date) {
+            date = date.pred();
+        }
+        date
+    }
+
+    /// The closest trading day strictly after `date`.
+    fn next_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date.succ();
+        while !self.is_trading_day(date) {
+            date = date.succ();
+        }
+        date
+    }
+}
+
+/// Rules is the calendar as the holiday rules alone describe it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rules;
+
+impl TradingCalendar for Rules {
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        is_trading_day(date)
+    }
+}
+
+/// The name of the holiday the exchange observes on `date`, if any.
+pub fn holiday(date: NaiveDate) -> Option<&'static str> {
+    holidays(date.year())
+        .into_iter()
+        .find(|(d, _)| *d == date)
+        .map(|(_, name)| name)
+}
+
+/// Every holiday the exchange observes in `year`, on its observed date.
+pub fn holidays(year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let mut days = vec![];
+    // New Year's Day moves to Monday from a Sunday, but the exchange doesn't
+    // close on the Friday before a Saturday New Year's Day.
+    let new_year = NaiveDate::from_ymd(year, 1, 1);
+    match new_year.weekday() {
+        Weekday::Sat => {},
+        Weekday::Sun => days.push((new_year.succ(), "New Year's Day")),
+        _ => days.push((new_year, "New Year's Day"))
+    }
+    if year >= 1998 {
+        days.push((nth_weekday(year, 1, Weekday::Mon, 3), "Martin Luther King, Jr. Day"));
+    }
+    days.push((nth_weekday(year, 2, Weekday::Mon, 3), "Washington's Birthday"));
+    days.push((easter(year) - Duration::days(2), "Good Friday"));
+    days.push((last_weekday(year, 5, Weekday::Mon), "Memorial Day"));
+    if year >= 2022 {
+        days.push((observed(NaiveDate::from_ymd(year, 6, 19)), "Juneteenth"));
+    }
+    days.push((observed(NaiveDate::from_ymd(year, 7, 4)), "Independence Day"));
+    days.push((nth_weekday(year, 9, Weekday::Mon, 1), "Labor Day"));
+    days.push((nth_weekday(year, 11, Weekday::Thu, 4), "Thanksgiving Day"));
+    days.push((observed(NaiveDate::from_ymd(year, 12, 25)), "Christmas Day"));
+    days
+}
+
+/// When the regular session ends early on `date`, the closing time.
+pub fn early_close(date: NaiveDate) -> Option<NaiveTime> {
+    if !is_trading_day(date) {
+        return None;
+    }
+    let year = date.year();
+    let early = [
+        // The day before Independence Day, unless that's the observed holiday.
+        NaiveDate::from_ymd(year, 7, 3),
+        // The day after Thanksgiving.
+        nth_weekday(year, 11, Weekday::Thu, 4).succ(),
+        // Christmas Eve, unless that's the observed holiday.
+        NaiveDate::from_ymd(year, 12, 24)
+    ];
+    if early.contains(&date) {
+        Some(NaiveTime::from_hms(EARLY_CLOSE.0, EARLY_CLOSE.1, 0))
+    } else {
+        None
+    }
+}
+
+/// Holidays on a Saturday are observed the Friday before, and on a Sunday
+/// the Monday after.
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date.pred(),
+        Weekday::Sun => date.succ(),
+        _ => date
+    }
+}
+
+/// The `n`th `weekday` of a month, counting from one.
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u32) -> NaiveDate {
+    NaiveDate::from_weekday_of_month(year, month, weekday, n as u8)
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    let first_of_next = if month == 12 {
+        NaiveDate::from_ymd(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd(year, month + 1, 1)
+    };
+    let mut date = first_of_next.pred();
+    while date.weekday() != weekday {
+        date = date.pred();
+    }
+    date
+}
+
+/// Easter Sunday in the Gregorian calendar (the anonymous algorithm).
+fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd(year, month, day)
+    }
+
+    #[test]
+    fn easter_falls_on_known_sundays() {
+        assert_eq!(easter(2008), date(2008, 3, 23));
+        assert_eq!(easter(2019), date(2019, 4, 21));
+        assert_eq!(easter(2020), date(2020, 4, 12));
+        assert_eq!(easter(2024), date(2024, 3, 31));
+        assert_eq!(easter(2038), date(2038, 4, 25));
+    }
+
+    #[test]
+    fn good_friday_is_closed() {
+        for good_friday in &[date(2019, 4, 19), date(2020, 4, 10), date(2021, 4, 2), date(2024, 3, 29), date(2025, 4, 18)] {
+            assert_eq!(holiday(*good_friday), Some("Good Friday"), "{}", good_friday);
+        }
+        assert_eq!(Rules.previous_trading_day(date(2020, 4, 13)), date(2020, 4, 9));
+    }
+
+    #[test]
+    fn weekend_independence_day_is_observed() {
+        // Saturday, observed the Friday before.
+        assert_eq!(holiday(date(2020, 7, 3)), Some("Independence Day"));
+        assert_eq!(holiday(date(2026, 7, 3)), Some("Independence Day"));
+        assert_eq!(Rules.next_trading_day(date(2020, 7, 2)), date(2020, 7, 6));
+        // Sunday, observed the Monday after.
+        assert_eq!(holiday(date(2021, 7, 5)), Some("Independence Day"));
+        assert_eq!(holiday(date(2027, 7, 5)), Some("Independence Day"));
+        assert_eq!(Rules.next_trading_day(date(2021, 7, 2)), date(2021, 7, 6));
+    }
+
+    #[test]
+    fn saturday_new_years_day_is_not_observed() {
+        assert!(is_trading_day(date(2021, 12, 31)));
+        assert!(is_trading_day(date(2010, 12, 31)));
+        assert!(!holidays(2022).iter().any(|(_, name)| *name == "New Year's Day"));
+        assert_eq!(Rules.next_trading_day(date(2021, 12, 30)), date(2021, 12, 31));
+        // Sunday is observed on the Monday.
+        assert_eq!(holiday(date(2023, 1, 2)), Some("New Year's Day"));
+        assert_eq!(Rules.previous_trading_day(date(2023, 1, 3)), date(2022, 12, 30));
+    }
+
+    #[test]
+    fn holidays_start_when_the_exchange_adopted_them() {
+        assert_eq!(holiday(date(1997, 1, 20)), None);
+        assert_eq!(holiday(date(1998, 1, 19)), Some("Martin Luther King, Jr. Day"));
+        assert!(is_trading_day(date(2021, 6, 18)));
+        // Juneteenth 2022 was a Sunday.
+        assert_eq!(holiday(date(2022, 6, 20)), Some("Juneteenth"));
+    }
+
+    #[test]
+    fn thanksgiving_and_christmas() {
+        assert_eq!(holiday(date(2020, 11, 26)), Some("Thanksgiving Day"));
+        assert_eq!(Rules.next_trading_day(date(2020, 11, 25)), date(2020, 11, 27));
+        assert_eq!(holiday(date(2021, 12, 24)), Some("Christmas Day"));
+        assert_eq!(holiday(date(2022, 12, 26)), Some("Christmas Day"));
+    }
+
+    #[test]
+    fn early_closes() {
+        let one_pm = Some(NaiveTime::from_hms(13, 0, 0));
+        for early in &[
+            date(2019, 7, 3), date(2019, 11, 29), date(2019, 12, 24),
+            date(2020, 11, 27), date(2020, 12, 24),
+            date(2023, 7, 3), date(2023, 11, 24),
+            date(2024, 7, 3), date(2024, 11, 29), date(2024, 12, 24)
+        ] {
+            assert_eq!(early_close(*early), one_pm, "{}", early);
+        }
+        // The day before is the observed holiday, or a weekend.
+        assert_eq!(early_close(date(2020, 7, 3)), None);
+        assert_eq!(early_close(date(2020, 7, 2)), None);
+        assert_eq!(early_close(date(2021, 12, 24)), None);
+        assert_eq!(early_close(date(2023, 12, 22)), None);
+        assert_eq!(early_close(date(2024, 7, 5)), None);
+    }
+
+    /// The rules, plus a one-off closure they don't know about.
+    struct Mourning;
+
+    impl TradingCalendar for Mourning {
+        fn is_trading_day(&self, date: NaiveDate) -> bool {
+            date != NaiveDate::from_ymd(2018, 12, 5) && is_trading_day(date)
+        }
+    }
+
+    #[test]
+    fn stepping_uses_the_calendar_given() {
+        assert_eq!(Rules.next_trading_day(date(2018, 12, 4)), date(2018, 12, 5));
+        assert_eq!(Mourning.next_trading_day(date(2018, 12, 4)), date(2018, 12, 6));
+        assert_eq!(Mourning.previous_trading_day(date(2018, 12, 6)), date(2018, 12, 4));
+        assert_eq!(Mourning.last_trading_day(date(2018, 12, 5)), date(2018, 12, 4));
+        assert_eq!(Mourning.last_trading_day(date(2018, 12, 6)), date(2018, 12, 6));
+    }
+}
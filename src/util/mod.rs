@@ -0,0 +1,78 @@
+pub mod calendar;
+
+use chrono::{Date, DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use chrono_tz::America::New_York;
+
+use crate::data::calendar::MarketCalendar;
+use calendar::TradingCalendar;
+
+/// The exchange's time zone: US Eastern, observing daylight saving time.
+pub fn eastern() -> Tz {
+    New_York
+}
+
+pub fn now() -> DateTime<Tz> {
+    Utc::now().with_timezone(&eastern())
+}
+
+pub fn today() -> Date<Tz> {
+    now().date()
+}
+
+/// `time` on `date` in exchange time. Wall times repeated when the clocks go
+/// back resolve to the first one; times skipped when they go forward are
+/// `None`.
+pub fn eastern_datetime(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Tz>> {
+    eastern().from_local_datetime(&date.and_time(time)).earliest()
+}
+
+/// Today if the market trades today, otherwise the trading day before it.
+pub fn last_market_open_day(calendar: &MarketCalendar) -> Date<Tz> {
+    let date = calendar.last_trading_day(today().naive_local());
+    eastern().from_local_date(&date).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn utc(date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&date.and_time(time))
+    }
+
+    #[test]
+    fn sessions_follow_daylight_saving() {
+        let open = NaiveTime::from_hms(9, 30, 0);
+        let close = NaiveTime::from_hms(16, 0, 0);
+        // Clocks went forward on 8 March 2020 and back on 1 November 2020.
+        for &(date, offset) in &[
+            (NaiveDate::from_ymd(2020, 3, 6), 5),
+            (NaiveDate::from_ymd(2020, 3, 8), 4),
+            (NaiveDate::from_ymd(2020, 3, 9), 4),
+            (NaiveDate::from_ymd(2020, 10, 30), 4),
+            (NaiveDate::from_ymd(2020, 11, 1), 5),
+            (NaiveDate::from_ymd(2020, 11, 2), 5)
+        ] {
+            for &time in &[open, close] {
+                let expected = utc(date, time) + Duration::hours(offset);
+                assert_eq!(eastern_datetime(date, time).unwrap(), expected, "{} {}", date, time);
+            }
+        }
+    }
+
+    #[test]
+    fn skipped_times_are_none() {
+        let spring_forward = NaiveDate::from_ymd(2020, 3, 8);
+        assert_eq!(eastern_datetime(spring_forward, NaiveTime::from_hms(2, 30, 0)), None);
+        assert!(eastern_datetime(spring_forward, NaiveTime::from_hms(3, 0, 0)).is_some());
+    }
+
+    #[test]
+    fn repeated_times_are_the_first() {
+        let fall_back = NaiveDate::from_ymd(2020, 11, 1);
+        let time = NaiveTime::from_hms(1, 30, 0);
+        assert_eq!(eastern_datetime(fall_back, time).unwrap(), utc(fall_back, time) + Duration::hours(4));
+    }
+}
@@ -0,0 +1,95 @@
+//! Drives `TradierClient` against the `mock_tradier` binary, so requests go
+//! over a real socket and come back through the client's own parsing.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveTime};
+use futures::StreamExt;
+use reqwest::Url;
+
+use stonks_cli::api::{ApiConfig, Environment, Interval, MarketDataProvider, SessionFilter, TradierClient};
+use stonks_cli::api::retry::RetryPolicy;
+use stonks_cli::api::stream::Reconnect;
+use stonks_cli::util;
+
+/// MockTradier is a running `mock_tradier`, killed when dropped.
+struct MockTradier {
+    child: Child,
+    base_url: Url
+}
+
+impl MockTradier {
+    fn start(args: &[&str]) -> Self {
+        let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+        let mut child = Command::new(env!("CARGO_BIN_EXE_mock_tradier"))
+            .args(["--port", "0", "--fixtures", fixtures])
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("mock_tradier should start");
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let address = line.trim().rsplit(' ').next().unwrap();
+        let base_url = Url::parse(address).unwrap_or_else(|_| panic!("unexpected banner {:?}", line));
+        MockTradier { child, base_url }
+    }
+
+    fn client(&self) -> TradierClient {
+        let mut config = ApiConfig::new(Environment::Custom(self.base_url.clone()));
+        config.api_key = Some("mock".to_string());
+        TradierClient::new(config).unwrap()
+    }
+}
+
+impl Drop for MockTradier {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[tokio::test]
+async fn quotes_come_back_for_the_requested_symbols() {
+    let mock = MockTradier::start(&[]);
+    let quotes = mock.client().get_stock_quotes(vec!["SPY".to_string(), "dis".to_string()]).await.unwrap();
+    let symbols: Vec<&str> = quotes.quotes().iter().map(|quote| quote.symbol.as_str()).collect();
+    assert_eq!(symbols, vec!["SPY", "DIS"]);
+}
+
+#[tokio::test]
+async fn timesales_parse_into_eastern_time() {
+    let mock = MockTradier::start(&[]);
+    let date = NaiveDate::from_ymd(2020, 7, 2);
+    let start = util::eastern_datetime(date, NaiveTime::from_hms(9, 30, 0)).unwrap();
+    let end = util::eastern_datetime(date, NaiveTime::from_hms(16, 0, 0)).unwrap();
+    let series = mock.client()
+        .get_time_series_data("SPY".to_string(), start, end, Interval::FiveMinutes, SessionFilter::Open)
+        .await
+        .unwrap();
+    assert!(!series.is_empty());
+    let first = &series.data()[0];
+    assert_eq!(first.time, start);
+    assert_eq!(i64::from(first.timestamp), start.timestamp());
+}
+
+#[tokio::test]
+async fn streams_reconnect_after_the_server_hangs_up() {
+    let mock = MockTradier::start(&["--stream-drop-after", "3"]);
+    let client = mock.client();
+    let mut reconnect = Reconnect::new(RetryPolicy {
+        max_retries: 0,
+        base_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(100)
+    });
+    for _ in 0..3 {
+        let events: Vec<_> = client.stream_events(vec!["SPY".to_string()]).await.unwrap().collect().await;
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|event| event.as_ref().unwrap().symbol() == Some("SPY")));
+        // Each connection delivered events, so the backoff never grows.
+        assert!(reconnect.delay(!events.is_empty()) <= Duration::from_millis(10));
+        assert_eq!(reconnect.attempts(), 1);
+    }
+}